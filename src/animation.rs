@@ -0,0 +1,127 @@
+use super::*;
+
+pub use config::{Event, Mode};
+
+#[derive(derive_more::Deref)]
+pub struct Animation {
+    #[deref]
+    pub frames: Vec<ugli::Texture>,
+    pub durations: Vec<f32>,
+    pub mode: Mode,
+    pub events: Vec<Event>,
+}
+
+impl Animation {
+    pub fn duration(&self) -> f32 {
+        self.durations.iter().sum()
+    }
+    fn frame_index(&self, time: f32) -> usize {
+        let mut end = 0.0;
+        for (index, duration) in self.durations.iter().enumerate() {
+            end += duration;
+            if time < end {
+                return index;
+            }
+        }
+        self.frames.len() - 1
+    }
+}
+
+async fn load_sidecar(geng: &Rc<Geng>, path: &str) -> anyhow::Result<config::Sidecar> {
+    let path = sidecar_path(path);
+    match <String as geng::LoadAsset>::load(geng, &path).await {
+        Ok(json) => {
+            Ok(serde_json::from_str(&json).map_err(|error| AssetError::Sidecar { path, error })?)
+        }
+        Err(_) => Ok(default()),
+    }
+}
+
+async fn load_sheet(
+    geng: &Rc<Geng>,
+    path: &str,
+) -> anyhow::Result<(frames::Frames, config::Sidecar)> {
+    let json = <String as geng::LoadAsset>::load(geng, path).await?;
+    let sheet: config::Sheet =
+        serde_json::from_str(&json).map_err(|error| AssetError::Sidecar {
+            path: path.to_owned(),
+            error,
+        })?;
+    let image_path = relative(path, &sheet.image);
+    let data = <Vec<u8> as geng::LoadAsset>::load(geng, &image_path).await?;
+    let image = frames::decode_image(&image_path, &data)?;
+    Ok((frames::slice(path, &sheet, &image)?, sheet.sidecar))
+}
+
+impl geng::LoadAsset for Animation {
+    fn load(geng: &Rc<Geng>, path: &str) -> geng::AssetFuture<Self> {
+        let geng = geng.clone();
+        let path = path.to_owned();
+        async move {
+            let (frames, sidecar) = if path.ends_with(".json") {
+                load_sheet(&geng, &path).await?
+            } else {
+                let data = <Vec<u8> as geng::LoadAsset>::load(&geng, &path).await?;
+                let frames = frames::decode_animation(&path, &data)?;
+                let sidecar = load_sidecar(&geng, &path).await?;
+                sidecar.validate(&sidecar_path(&path), Some(frames.len()))?;
+                (frames, sidecar)
+            };
+            let (frames, durations) = frames
+                .into_iter()
+                .map(|(image, duration)| {
+                    (
+                        ugli::Texture::from_image_image(geng.ugli(), image),
+                        duration / sidecar.speed,
+                    )
+                })
+                .unzip();
+            Ok(Self {
+                frames,
+                durations,
+                mode: sidecar.mode,
+                events: sidecar.events,
+            })
+        }
+        .boxed_local()
+    }
+    const DEFAULT_EXT: Option<&'static str> = Some("png");
+}
+
+#[derive(Default)]
+pub struct Playback {
+    time: f32,
+}
+
+impl Playback {
+    pub fn reset(&mut self) {
+        self.time = 0.0;
+    }
+    pub fn update<'a>(&mut self, animation: &'a Animation, delta_time: f32) -> Vec<&'a str> {
+        let total = animation.duration();
+        let before = animation.frame_index(self.time);
+        self.time += delta_time;
+        let mut laps = 0;
+        match animation.mode {
+            Mode::Loop => {
+                laps = (self.time / total).floor() as usize;
+                self.time -= laps as f32 * total;
+            }
+            Mode::Once => self.time = self.time.min(total),
+        }
+        let after = animation.frame_index(self.time) + laps.min(1) * animation.len();
+        (before + 1..=after)
+            .flat_map(|index| {
+                let frame = index % animation.len();
+                animation
+                    .events
+                    .iter()
+                    .filter(move |event| event.frame == frame)
+                    .map(|event| event.name.as_str())
+            })
+            .collect()
+    }
+    pub fn frame<'a>(&self, animation: &'a Animation) -> &'a ugli::Texture {
+        &animation[animation.frame_index(self.time)]
+    }
+}
@@ -0,0 +1,317 @@
+use super::*;
+
+const MAX_VOLUME: f64 = 0.2;
+const DUCK_AMOUNT: f64 = 0.6;
+const DUCK_RELEASE: f64 = 1.5;
+const CALM_LEVEL: f64 = 0.4;
+const INTENSITY_RATE: f64 = 0.5;
+const PAN_WIDTH: f32 = 1.0;
+
+pub use config::Bus;
+
+fn spatial(offset: Vec2<f32>, range: f32) -> (f64, f64) {
+    let gain = (1.0 - offset.len() / range).max(0.0);
+    let pan = clamp(offset.x / PAN_WIDTH, -1.0..=1.0);
+    (gain as f64, pan as f64)
+}
+
+fn set_volume(effects: &mut [geng::SoundEffect], volume: f64, pan: f64) {
+    match effects {
+        [left, right] => {
+            let angle = (pan + 1.0) * std::f64::consts::FRAC_PI_4;
+            left.set_volume(volume * angle.cos());
+            right.set_volume(volume * angle.sin());
+        }
+        effects => {
+            for effect in effects {
+                effect.set_volume(volume);
+            }
+        }
+    }
+}
+
+#[derive(Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct BusSettings {
+    pub volume: f64,
+    pub muted: bool,
+}
+
+impl Default for BusSettings {
+    fn default() -> Self {
+        Self {
+            volume: 0.5,
+            muted: false,
+        }
+    }
+}
+
+#[derive(Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Settings {
+    pub music: BusSettings,
+    pub sfx: BusSettings,
+    pub voices: BusSettings,
+}
+
+impl Settings {
+    pub fn bus(&self, bus: Bus) -> &BusSettings {
+        match bus {
+            Bus::Music => &self.music,
+            Bus::Sfx => &self.sfx,
+            Bus::Voices => &self.voices,
+        }
+    }
+    fn volume(&self, bus: Bus) -> f64 {
+        let bus = self.bus(bus);
+        if bus.muted {
+            0.0
+        } else {
+            bus.volume * MAX_VOLUME
+        }
+    }
+    pub fn bus_mut(&mut self, bus: Bus) -> &mut BusSettings {
+        match bus {
+            Bus::Music => &mut self.music,
+            Bus::Sfx => &mut self.sfx,
+            Bus::Voices => &mut self.voices,
+        }
+    }
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct Track(u64);
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub struct Emitter(u64);
+
+#[derive(Default)]
+struct EventState {
+    cooldown: f32,
+    voices: Vec<f32>,
+}
+
+struct EmitterState {
+    bus: Bus,
+    volume: f64,
+    range: f32,
+    gain: f64,
+    pan: f64,
+    effects: Vec<geng::SoundEffect>,
+}
+
+pub struct Mixer {
+    settings: Rc<RefCell<save::SaveData<super::Settings>>>,
+    events: HashMap<String, EventState>,
+    unknown_events: HashSet<String>,
+    music: Option<(Track, geng::SoundEffect)>,
+    next_track: u64,
+    emitters: HashMap<Emitter, EmitterState>,
+    next_emitter: u64,
+    duck: f64,
+    intensity: f64,
+    target_intensity: f64,
+}
+
+impl Mixer {
+    pub fn new(settings: &Rc<RefCell<save::SaveData<super::Settings>>>) -> Self {
+        Self {
+            settings: settings.clone(),
+            events: HashMap::new(),
+            unknown_events: HashSet::new(),
+            music: None,
+            next_track: 0,
+            emitters: HashMap::new(),
+            next_emitter: 0,
+            duck: 0.0,
+            intensity: 0.0,
+            target_intensity: 0.0,
+        }
+    }
+    fn volume(&self, bus: Bus) -> f64 {
+        self.settings.borrow().audio.volume(bus)
+    }
+    fn music_volume(&self) -> f64 {
+        self.volume(Bus::Music)
+            * (CALM_LEVEL + (1.0 - CALM_LEVEL) * self.intensity)
+            * (1.0 - DUCK_AMOUNT * self.duck)
+    }
+    fn event<'a>(&mut self, table: &'a sounds::Table, name: &str) -> Option<&'a sounds::Event> {
+        let event = table.get(name);
+        if event.is_none() && self.unknown_events.insert(name.to_owned()) {
+            warn!("Unknown sound event {:?}", name);
+        }
+        event
+    }
+    fn play_with_gain(
+        &mut self,
+        event: &sounds::Event,
+        sample: &sounds::Sample,
+        gain: f64,
+        pan: f64,
+    ) {
+        let mut effects = sample.effects();
+        set_volume(&mut effects, self.volume(event.bus) * gain, pan);
+        for effect in &mut effects {
+            effect.play();
+        }
+        if event.duck {
+            self.duck = self.duck.max(gain);
+        }
+    }
+    fn trigger_with_offset(
+        &mut self,
+        table: &sounds::Table,
+        name: &str,
+        offset: Option<Vec2<f32>>,
+    ) {
+        let event = match self.event(table, name) {
+            Some(event) => event,
+            None => return,
+        };
+        let (gain, pan) = match offset {
+            Some(offset) => spatial(offset, event.range),
+            None => (1.0, 0.0),
+        };
+        if gain <= 0.0 {
+            return;
+        }
+        let state = self.events.entry(name.to_owned()).or_default();
+        if state.cooldown > 0.0 || state.voices.len() >= event.max_voices {
+            return;
+        }
+        let sample = match event.samples.choose(&mut rand::thread_rng()) {
+            Some(sample) => sample,
+            None => return,
+        };
+        state.cooldown = event.cooldown;
+        state.voices.push(event.duration);
+        let (min_volume, max_volume) = event.volume;
+        let volume = rand::thread_rng().gen_range(min_volume..=max_volume);
+        self.play_with_gain(event, sample, gain * volume, pan);
+    }
+    pub fn trigger(&mut self, table: &sounds::Table, name: &str) {
+        self.trigger_with_offset(table, name, None);
+    }
+    pub fn trigger_at(&mut self, table: &sounds::Table, name: &str, offset: Vec2<f32>) {
+        self.trigger_with_offset(table, name, Some(offset));
+    }
+    pub fn start_emitter(&mut self, table: &sounds::Table, name: &str) -> Option<Emitter> {
+        let event = self.event(table, name)?;
+        let sample = event.samples.choose(&mut rand::thread_rng())?;
+        let emitter = Emitter(self.next_emitter);
+        self.next_emitter += 1;
+        let (min_volume, max_volume) = event.volume;
+        let mut effects = sample.effects();
+        set_volume(&mut effects, 0.0, 0.0);
+        for effect in &mut effects {
+            effect.play();
+        }
+        self.emitters.insert(
+            emitter,
+            EmitterState {
+                bus: event.bus,
+                volume: rand::thread_rng().gen_range(min_volume..=max_volume),
+                range: event.range,
+                gain: 0.0,
+                pan: 0.0,
+                effects,
+            },
+        );
+        Some(emitter)
+    }
+    pub fn move_emitter(&mut self, emitter: Emitter, offset: Vec2<f32>) {
+        if let Some(state) = self.emitters.get_mut(&emitter) {
+            let (gain, pan) = spatial(offset, state.range);
+            state.gain = gain;
+            state.pan = pan;
+        }
+    }
+    pub fn mute_emitter(&mut self, emitter: Emitter) {
+        if let Some(state) = self.emitters.get_mut(&emitter) {
+            state.gain = 0.0;
+        }
+    }
+    pub fn pause_emitter(&mut self, emitter: Emitter) {
+        if let Some(state) = self.emitters.get_mut(&emitter) {
+            for effect in &mut state.effects {
+                effect.pause();
+            }
+        }
+    }
+    pub fn resume_emitter(&mut self, emitter: Emitter) {
+        if let Some(state) = self.emitters.get_mut(&emitter) {
+            for effect in &mut state.effects {
+                effect.play();
+            }
+        }
+    }
+    pub fn stop_emitter(&mut self, emitter: Emitter) {
+        self.pause_emitter(emitter);
+        self.emitters.remove(&emitter);
+    }
+    pub fn play_music(&mut self, sound: &geng::Sound) -> Track {
+        if let Some((_, mut music)) = self.music.take() {
+            music.pause();
+        }
+        let track = Track(self.next_track);
+        self.next_track += 1;
+        let mut music = sound.effect();
+        music.set_volume(self.music_volume());
+        music.play();
+        self.music = Some((track, music));
+        track
+    }
+    fn music(&mut self, track: Track) -> Option<&mut geng::SoundEffect> {
+        match &mut self.music {
+            Some((current, music)) if *current == track => Some(music),
+            _ => None,
+        }
+    }
+    pub fn pause_music(&mut self, track: Track) {
+        if let Some(music) = self.music(track) {
+            music.pause();
+        }
+    }
+    pub fn resume_music(&mut self, track: Track) {
+        if let Some(music) = self.music(track) {
+            music.play();
+        }
+    }
+    pub fn stop_music(&mut self, track: Track) {
+        if let Some(music) = self.music(track) {
+            music.pause();
+            self.music = None;
+        }
+    }
+    pub fn set_intensity(&mut self, intensity: f64) {
+        self.target_intensity = intensity;
+    }
+    pub fn update(&mut self, delta_time: f64) {
+        for state in self.events.values_mut() {
+            state.cooldown -= delta_time as f32;
+            for voice in &mut state.voices {
+                *voice -= delta_time as f32;
+            }
+            state.voices.retain(|&voice| voice > 0.0);
+        }
+        self.duck = (self.duck - delta_time * DUCK_RELEASE).max(0.0);
+        self.intensity += clamp_abs(
+            self.target_intensity - self.intensity,
+            delta_time * INTENSITY_RATE,
+        );
+        let settings = self.settings.borrow();
+        for state in self.emitters.values_mut() {
+            set_volume(
+                &mut state.effects,
+                settings.audio.volume(state.bus) * state.volume * state.gain,
+                state.pan,
+            );
+        }
+        drop(settings);
+        let volume = self.music_volume();
+        if let Some((_, music)) = &mut self.music {
+            music.set_volume(volume);
+        }
+    }
+}
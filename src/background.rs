@@ -0,0 +1,103 @@
+use super::*;
+
+pub const LAYERS: &str = "background/layers.json";
+
+struct Layer {
+    texture: ugli::Texture,
+    config: config::Layer,
+}
+
+pub struct Assets {
+    layers: Vec<Layer>,
+}
+
+impl geng::LoadAsset for Assets {
+    fn load(geng: &Rc<Geng>, path: &str) -> geng::AssetFuture<Self> {
+        let json = <String as geng::LoadAsset>::load(geng, path);
+        let geng = geng.clone();
+        let path = path.to_owned();
+        async move {
+            let configs: Vec<config::Layer> =
+                serde_json::from_str(&json.await?).map_err(|error| AssetError::Sidecar {
+                    path: path.clone(),
+                    error,
+                })?;
+            let mut layers = Vec::new();
+            for config in configs {
+                config.validate(&path)?;
+                let texture = <ugli::Texture as geng::LoadAsset>::load(
+                    &geng,
+                    &relative(&path, &config.image),
+                )
+                .await?;
+                layers.push(Layer { texture, config });
+            }
+            Ok(Self { layers })
+        }
+        .boxed_local()
+    }
+    const DEFAULT_EXT: Option<&'static str> = Some("json");
+}
+
+impl Assets {
+    pub fn prepare(&mut self) -> Result<(), AssetError> {
+        for layer in &mut self.layers {
+            let size = layer.texture.size();
+            power_of_two(
+                &relative(LAYERS, &layer.config.image),
+                size.x as u32,
+                size.y as u32,
+            )?;
+            layer.texture.set_wrap_mode(ugli::WrapMode::Repeat);
+        }
+        Ok(())
+    }
+    pub fn draw(
+        &self,
+        geng: &Geng,
+        framebuffer: &mut ugli::Framebuffer,
+        position: Vec2<f32>,
+        color: Color<f32>,
+    ) {
+        let framebuffer_size = framebuffer.size().map(|x| x as f32);
+        let horizon = framebuffer_size.y * 0.8;
+        let sky_height = framebuffer_size.y - horizon;
+        for Layer { texture, config } in &self.layers {
+            let texture_size = texture.size().map(|x| x as f32);
+            let height = sky_height * config.height;
+            let tile_width = height * texture_size.x / texture_size.y;
+            let y1 = horizon + sky_height * config.elevation;
+            let y2 = y1 + height;
+            let vt1 = position.x * config.parallax + position.y * config.scroll;
+            let vt2 = vt1 + framebuffer_size.x / tile_width;
+            geng.draw_2d().draw_textured(
+                framebuffer,
+                &[
+                    geng::draw_2d::TexturedVertex {
+                        a_color: color,
+                        a_pos: vec2(0.0, y1),
+                        a_vt: vec2(vt1, 0.0),
+                    },
+                    geng::draw_2d::TexturedVertex {
+                        a_color: color,
+                        a_pos: vec2(framebuffer_size.x, y1),
+                        a_vt: vec2(vt2, 0.0),
+                    },
+                    geng::draw_2d::TexturedVertex {
+                        a_color: color,
+                        a_pos: vec2(framebuffer_size.x, y2),
+                        a_vt: vec2(vt2, 1.0),
+                    },
+                    geng::draw_2d::TexturedVertex {
+                        a_color: color,
+                        a_pos: vec2(0.0, y2),
+                        a_vt: vec2(vt1, 1.0),
+                    },
+                ],
+                texture,
+                Color::WHITE,
+                ugli::DrawMode::TriangleFan,
+            );
+        }
+    }
+}
@@ -0,0 +1,286 @@
+use image::RgbaImage;
+use serde::de::DeserializeOwned;
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
+use tsunami_run::{
+    config, frames, pipeline, power_of_two, relative, sidecar_path, wav, AssetError,
+};
+
+const MAX_PADDING: f32 = 0.25;
+
+struct Validator {
+    dir: PathBuf,
+    problems: Vec<String>,
+    animation_events: Vec<(String, String)>,
+    sound_events: HashSet<String>,
+}
+
+impl Validator {
+    fn problem(&mut self, path: &str, message: impl std::fmt::Display) {
+        self.problems.push(format!("{}: {}", path, message));
+    }
+    fn check<T, E: std::fmt::Display>(&mut self, path: &str, result: Result<T, E>) -> Option<T> {
+        match result {
+            Ok(value) => Some(value),
+            Err(e) => {
+                self.problem(path, e);
+                None
+            }
+        }
+    }
+    fn report<T>(&mut self, result: Result<T, AssetError>) -> Option<T> {
+        match result {
+            Ok(value) => Some(value),
+            Err(e) => {
+                self.problems.push(e.to_string());
+                None
+            }
+        }
+    }
+    fn read(&mut self, path: &str) -> Option<Vec<u8>> {
+        let result = std::fs::read(self.dir.join(path));
+        self.check(path, result)
+    }
+    fn json<T: DeserializeOwned>(&mut self, path: &str) -> Option<T> {
+        let data = self.read(path)?;
+        self.check(path, serde_json::from_slice(&data))
+    }
+    fn texture(&mut self, path: &str) -> Option<RgbaImage> {
+        let data = self.read(path)?;
+        let image = self.report(frames::decode_image(path, &data))?;
+        self.check_size(path, &image);
+        Some(image)
+    }
+    fn check_size(&mut self, path: &str, image: &RgbaImage) {
+        if image.width() > frames::MAX_TEXTURE_SIZE || image.height() > frames::MAX_TEXTURE_SIZE {
+            self.problem(
+                path,
+                format_args!(
+                    "{}x{} exceeds the {}px texture limit",
+                    image.width(),
+                    image.height(),
+                    frames::MAX_TEXTURE_SIZE,
+                ),
+            );
+        }
+    }
+    fn check_padding(&mut self, path: &str, image: &RgbaImage) {
+        let opaque = |x: u32, y: u32| image.get_pixel(x, y)[3] != 0;
+        let (width, height) = image.dimensions();
+        let rows: Vec<u32> = (0..height)
+            .filter(|&y| (0..width).any(|x| opaque(x, y)))
+            .collect();
+        let columns: Vec<u32> = (0..width)
+            .filter(|&x| (0..height).any(|y| opaque(x, y)))
+            .collect();
+        let (top, bottom, left, right) =
+            match (rows.first(), rows.last(), columns.first(), columns.last()) {
+                (Some(&top), Some(&bottom), Some(&left), Some(&right)) => {
+                    (top, bottom, left, right)
+                }
+                _ => {
+                    self.problem(path, "image is fully transparent");
+                    return;
+                }
+            };
+        for (side, padding, size) in [
+            ("top", top, height),
+            ("bottom", height - 1 - bottom, height),
+            ("left", left, width),
+            ("right", width - 1 - right, width),
+        ] {
+            if padding as f32 > size as f32 * MAX_PADDING {
+                self.problem(
+                    path,
+                    format_args!("{}px of transparent padding on the {}", padding, side),
+                );
+            }
+        }
+    }
+    fn repeating_texture(&mut self, path: &str) {
+        if let Some(image) = self.texture(path) {
+            self.report(power_of_two(path, image.width(), image.height()));
+        }
+    }
+    fn sprite(&mut self, path: &str) {
+        if let Some(image) = self.texture(path) {
+            self.check_padding(path, &image);
+        }
+    }
+    fn events(&mut self, path: &str, sidecar: &config::Sidecar) {
+        for event in &sidecar.events {
+            self.animation_events
+                .push((path.to_owned(), event.name.clone()));
+        }
+    }
+    fn sheet(&mut self, path: &str) -> Option<frames::Frames> {
+        let sheet: config::Sheet = self.json(path)?;
+        let image_path = relative(path, &sheet.image);
+        let image = self.texture(&image_path)?;
+        self.events(path, &sheet.sidecar);
+        self.report(frames::slice(path, &sheet, &image))
+    }
+    fn animation(&mut self, path: &str) -> Option<frames::Frames> {
+        if path.ends_with(".json") {
+            return self.sheet(path);
+        }
+        let data = self.read(path)?;
+        let frames = self.report(frames::decode_animation(path, &data))?;
+        let (first, _) = &frames[0];
+        self.check_size(path, first);
+        for (index, (frame, _)) in frames.iter().enumerate() {
+            if frame.dimensions() != first.dimensions() {
+                self.problem(
+                    path,
+                    format_args!(
+                        "frame {} is {}x{}, expected {}x{}",
+                        index,
+                        frame.width(),
+                        frame.height(),
+                        first.width(),
+                        first.height(),
+                    ),
+                );
+            }
+        }
+        let sidecar = sidecar_path(path);
+        if self.dir.join(&sidecar).exists() {
+            if let Some(value) = self.json::<config::Sidecar>(&sidecar) {
+                self.report(value.validate(&sidecar, Some(frames.len())));
+                self.events(&sidecar, &value);
+            }
+        }
+        Some(frames)
+    }
+    fn skin(&mut self, path: &str) {
+        let manifest: config::Skin = match self.json(path) {
+            Some(manifest) => manifest,
+            None => return,
+        };
+        self.report(manifest.validate(path));
+        for (_, props) in manifest.props() {
+            for prop in props {
+                self.sprite(&relative(path, &prop.path));
+            }
+        }
+        for (_, pose) in manifest.character.poses() {
+            self.animation(&relative(path, &pose.path));
+        }
+    }
+    fn skins(&mut self, path: &str) {
+        let entries: Vec<config::SkinEntry> = match self.json(path) {
+            Some(entries) => entries,
+            None => return,
+        };
+        if entries.is_empty() {
+            self.problem(path, "expected a non-empty list of skins");
+        }
+        for entry in &entries {
+            self.skin(&relative(path, &entry.manifest));
+        }
+    }
+    fn sounds(&mut self, path: &str) {
+        let events: HashMap<String, config::Sound> = match self.json(path) {
+            Some(events) => events,
+            None => return,
+        };
+        self.sound_events.extend(events.keys().cloned());
+        for (name, event) in &events {
+            let event_path = format!("{}: {}", path, name);
+            self.report(event.validate(&event_path));
+            for sample in event.variants() {
+                let samples = if event.panned {
+                    wav::panned_paths(&sample).to_vec()
+                } else {
+                    vec![sample]
+                };
+                for sample in samples {
+                    if !self.dir.join(relative(path, &sample)).exists() {
+                        self.problem(&event_path, format_args!("missing {:?}", sample));
+                    }
+                }
+            }
+        }
+    }
+    fn background(&mut self, path: &str) {
+        let layers: Vec<config::Layer> = match self.json(path) {
+            Some(layers) => layers,
+            None => return,
+        };
+        for layer in &layers {
+            self.report(layer.validate(path));
+            self.repeating_texture(&relative(path, &layer.image));
+        }
+    }
+}
+
+fn main() {
+    let mut args: Vec<String> = std::env::args().skip(1).collect();
+    let write = match args.iter().position(|arg| arg == "--write") {
+        Some(index) => {
+            args.remove(index);
+            true
+        }
+        None => false,
+    };
+    let dir = args.pop().unwrap_or_else(|| "static".to_owned());
+    let dir = Path::new(&dir);
+    let source_dir = dir
+        .parent()
+        .unwrap_or_else(|| Path::new(""))
+        .join(pipeline::SOURCE_DIR);
+    if write {
+        if let Err(e) = pipeline::write(&source_dir, dir) {
+            eprintln!("error: {:#}", e);
+            std::process::exit(1);
+        }
+        println!("Generated assets in {}", dir.display());
+    }
+    let mut validator = Validator {
+        dir: dir.to_owned(),
+        problems: Vec::new(),
+        animation_events: Vec::new(),
+        sound_events: HashSet::new(),
+    };
+    validator.skins("skins.json");
+    validator.sounds("sounds.json");
+    validator.json::<config::Water>("water/config.json");
+    validator.background("background/layers.json");
+    for path in ["road.png", "sand_road.png", "pierce.png"] {
+        validator.repeating_texture(path);
+    }
+    validator.texture("sit.png");
+    validator.read("music.mp3");
+    validator.animation(&format!("{}.json", pipeline::TSUNAMI_SHEET));
+    let mut animation_events = std::mem::take(&mut validator.animation_events);
+    animation_events.sort();
+    animation_events.dedup();
+    for (path, name) in animation_events {
+        if !validator.sound_events.contains(&name) {
+            validator.problem(
+                &path,
+                format_args!("event {:?} has no entry in sounds.json", name),
+            );
+        }
+    }
+    match pipeline::stale(&source_dir, dir) {
+        Ok(Some(reason)) => validator.problem(
+            pipeline::SOURCE_DIR,
+            format_args!("{}, run with --write to regenerate", reason),
+        ),
+        Ok(None) => {}
+        Err(e) => validator.problem(pipeline::SOURCE_DIR, format_args!("{:#}", e)),
+    }
+    if !validator.problems.is_empty() {
+        for problem in &validator.problems {
+            eprintln!("error: {}", problem);
+        }
+        eprintln!(
+            "{} problem(s) found in {}",
+            validator.problems.len(),
+            dir.display()
+        );
+        std::process::exit(1);
+    }
+    println!("Assets in {} are valid", dir.display());
+}
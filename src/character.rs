@@ -1,10 +1,15 @@
 use super::*;
 
-#[derive(geng::Assets)]
+pub struct Pose {
+    pub animation: Animation,
+    pub origin: Vec2<f32>,
+    pub scale: f32,
+}
+
 pub struct Assets {
-    run: Animation,
-    fall: Animation,
-    fall_side: Animation,
+    pub run: Pose,
+    pub fall: Pose,
+    pub fall_side: Pose,
 }
 
 #[derive(PartialEq, Eq)]
@@ -17,7 +22,7 @@ pub enum State {
 pub struct Character {
     assets: Rc<Assets>,
     pub state: State,
-    animation_position: f32,
+    playback: animation::Playback,
     pub position: Vec2<f32>,
     pub velocity: Vec2<f32>,
 }
@@ -28,35 +33,45 @@ impl Character {
             assets,
             state: State::Run,
             position,
-            animation_position: 0.0,
+            playback: default(),
             velocity: vec2(0.0, 0.0),
         }
     }
-    pub fn update(&mut self, delta_time: f32) {
+    pub fn set_assets(&mut self, assets: Rc<Assets>) {
+        self.assets = assets;
+    }
+    fn pose(&self) -> &Pose {
+        match self.state {
+            State::Fall => &self.assets.fall,
+            State::FallSide => &self.assets.fall_side,
+            State::Run => &self.assets.run,
+        }
+    }
+    pub fn update(&mut self, delta_time: f32) -> Vec<&str> {
         match self.state {
             State::Run => {
                 self.position += self.velocity * delta_time;
-                self.animation_position += 3.0 * delta_time * self.velocity.y.abs();
-                while self.animation_position >= 1.0 {
-                    self.animation_position -= 1.0;
-                }
+                self.playback.update(
+                    &self.assets.run.animation,
+                    delta_time * self.velocity.y.abs(),
+                )
             }
-            State::FallSide | State::Fall => self.animation_position += 2.0 * delta_time,
+            State::Fall => self
+                .playback
+                .update(&self.assets.fall.animation, delta_time),
+            State::FallSide => self
+                .playback
+                .update(&self.assets.fall_side.animation, delta_time),
         }
     }
     pub fn draw(&self) -> (&ugli::Texture, Vec3<f32>, Vec2<f32>, Size) {
-        let (animation, origin, size): (&Animation, Vec2<f32>, f32) = match self.state {
-            State::Fall => (&self.assets.fall, vec2(0.5, 0.5), 0.7),
-            State::FallSide => (&self.assets.fall_side, vec2(0.5, 0.0), 2.3),
-            State::Run => (&self.assets.run, vec2(0.5, 0.0), 1.0),
-        };
-        let texture = &animation[((self.animation_position * animation.len() as f32) as usize)
-            .min(animation.len() - 1)];
+        let pose = self.pose();
+        let texture = self.playback.frame(&pose.animation);
         (
             texture,
             self.position.extend(0.0),
-            origin,
-            Size::FixedWidth(PLAYER_SIZE * 2.5 * size),
+            pose.origin,
+            Size::FixedWidth(PLAYER_SIZE * 2.5 * pose.scale),
         )
     }
     pub fn check_hit(&mut self, obstacle_position: Vec2<f32>, obstacle_size: f32) -> bool {
@@ -77,20 +92,22 @@ impl Character {
         }
         false
     }
-    pub fn fall(&mut self) {
+    pub fn fall(&mut self) -> bool {
         if self.state != State::Run {
-            return;
+            return false;
         }
         self.velocity = vec2(0.0, 0.0);
-        self.animation_position = 0.0;
+        self.playback.reset();
         self.state = State::Fall;
+        true
     }
-    pub fn fall_side(&mut self) {
+    pub fn fall_side(&mut self) -> bool {
         if self.state != State::Run {
-            return;
+            return false;
         }
         self.velocity = vec2(0.0, 0.0);
-        self.animation_position = 0.0;
+        self.playback.reset();
         self.state = State::FallSide;
+        true
     }
 }
@@ -0,0 +1,319 @@
+use super::*;
+
+pub const DEFAULT_FRAME_DURATION: f32 = 0.1;
+pub const DEFAULT_RANGE: f32 = 5.0;
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Mode {
+    Loop,
+    Once,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Event {
+    pub frame: usize,
+    pub name: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Sidecar {
+    pub mode: Mode,
+    pub speed: f32,
+    pub events: Vec<Event>,
+}
+
+impl Default for Sidecar {
+    fn default() -> Self {
+        Self {
+            mode: Mode::Loop,
+            speed: 1.0,
+            events: Vec::new(),
+        }
+    }
+}
+
+impl Sidecar {
+    pub fn validate(&self, path: &str, frames: Option<usize>) -> Result<(), AssetError> {
+        positive(path, "speed", self.speed)?;
+        if let Some(frames) = frames {
+            for event in &self.events {
+                in_range(
+                    path,
+                    "event frame",
+                    event.frame as f64,
+                    0.0..=frames as f64 - 1.0,
+                )?;
+            }
+        }
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Sheet {
+    pub image: String,
+    pub frame_width: u32,
+    pub frame_height: u32,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub frames: Option<usize>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub frame_duration: Option<f32>,
+    #[serde(flatten)]
+    pub sidecar: Sidecar,
+}
+
+impl Sheet {
+    pub fn frame_duration(&self) -> f32 {
+        self.frame_duration.unwrap_or(DEFAULT_FRAME_DURATION)
+    }
+    pub fn columns(&self, width: u32) -> u32 {
+        width.checked_div(self.frame_width).unwrap_or(0)
+    }
+    pub fn capacity(&self, width: u32, height: u32) -> usize {
+        self.columns(width) as usize * height.checked_div(self.frame_height).unwrap_or(0) as usize
+    }
+    pub fn frame_count(&self, width: u32, height: u32) -> usize {
+        let capacity = self.capacity(width, height);
+        self.frames.unwrap_or(capacity).min(capacity)
+    }
+    pub fn validate(&self, path: &str, width: u32, height: u32) -> Result<(), AssetError> {
+        positive(path, "frame_duration", self.frame_duration())?;
+        power_of_two(path, self.frame_width, self.frame_height)?;
+        let capacity = self.capacity(width, height);
+        if let Some(frames) = self.frames {
+            in_range(path, "frames", frames as f64, 1.0..=capacity as f64)?;
+        }
+        self.sidecar
+            .validate(path, Some(self.frame_count(width, height)))
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Prop {
+    pub path: String,
+    pub origin: Vec2<f32>,
+    pub width: f32,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Pose {
+    pub path: String,
+    pub origin: Vec2<f32>,
+    pub scale: f32,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Character {
+    pub run: Pose,
+    pub fall: Pose,
+    pub fall_side: Pose,
+}
+
+impl Character {
+    pub fn poses(&self) -> [(&'static str, &Pose); 3] {
+        [
+            ("run", &self.run),
+            ("fall", &self.fall),
+            ("fall_side", &self.fall_side),
+        ]
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Skin {
+    pub houses: Vec<Prop>,
+    pub beach_houses: Vec<Prop>,
+    pub cars: Vec<Prop>,
+    pub character: Character,
+}
+
+impl Skin {
+    pub fn props(&self) -> [(&'static str, &[Prop]); 3] {
+        [
+            ("houses", &self.houses),
+            ("beach_houses", &self.beach_houses),
+            ("cars", &self.cars),
+        ]
+    }
+    pub fn validate(&self, path: &str) -> Result<(), AssetError> {
+        for (field, props) in self.props() {
+            if props.is_empty() {
+                return Err(AssetError::Manifest {
+                    path: path.to_owned(),
+                    field,
+                });
+            }
+            for prop in props {
+                positive(path, "width", prop.width)?;
+            }
+        }
+        for (_, pose) in self.character.poses() {
+            positive(path, "scale", pose.scale)?;
+        }
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SkinEntry {
+    pub name: String,
+    pub manifest: String,
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Bus {
+    Music,
+    Sfx,
+    Voices,
+}
+
+fn default_range() -> f32 {
+    DEFAULT_RANGE
+}
+
+fn default_pitch() -> (f64, f64) {
+    (1.0, 1.0)
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Sound {
+    pub bus: Bus,
+    pub samples: Vec<String>,
+    pub volume: (f64, f64),
+    pub cooldown: f32,
+    pub max_voices: usize,
+    pub duration: f32,
+    #[serde(default)]
+    pub panned: bool,
+    #[serde(default)]
+    pub looped: bool,
+    #[serde(default = "default_range")]
+    pub range: f32,
+    #[serde(default = "default_pitch")]
+    pub pitch: (f64, f64),
+    #[serde(default)]
+    pub duck: bool,
+}
+
+impl Sound {
+    pub fn validate(&self, path: &str) -> Result<(), AssetError> {
+        if self.samples.is_empty() {
+            return Err(AssetError::Manifest {
+                path: path.to_owned(),
+                field: "samples",
+            });
+        }
+        let (min, max) = self.volume;
+        in_range(path, "volume", min, 0.0..=1.0)?;
+        in_range(path, "volume", max, min..=1.0)?;
+        in_range(path, "cooldown", self.cooldown as f64, 0.0..=f64::INFINITY)?;
+        in_range(
+            path,
+            "max_voices",
+            self.max_voices as f64,
+            1.0..=f64::INFINITY,
+        )?;
+        positive(path, "duration", self.duration)?;
+        positive(path, "range", self.range)?;
+        let (min, max) = self.pitch;
+        let percent = |pitch: u32| pitch as f64 / 100.0;
+        let highest = percent(wav::PITCHES[wav::PITCHES.len() - 1]);
+        in_range(path, "pitch", min, percent(wav::PITCHES[0])..=highest)?;
+        in_range(path, "pitch", max, min..=highest)?;
+        Ok(())
+    }
+    pub fn variants(&self) -> Vec<String> {
+        let (min, max) = self.pitch;
+        let percent = |pitch: f64| (pitch * 100.0).round() as u32;
+        let pitches: Vec<u32> = wav::PITCHES
+            .iter()
+            .copied()
+            .filter(|pitch| (percent(min)..=percent(max)).contains(pitch))
+            .collect();
+        self.samples
+            .iter()
+            .flat_map(|sample| {
+                pitches
+                    .iter()
+                    .map(move |&pitch| wav::pitched_path(sample, pitch))
+            })
+            .collect()
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Layer {
+    pub image: String,
+    pub parallax: f32,
+    pub scroll: f32,
+    pub elevation: f32,
+    pub height: f32,
+}
+
+impl Layer {
+    pub fn validate(&self, path: &str) -> Result<(), AssetError> {
+        positive(path, "height", self.height)?;
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Water {
+    pub enabled: bool,
+    pub deep_color: Color<f32>,
+    pub shallow_color: Color<f32>,
+    pub foam_color: Color<f32>,
+    pub crest_height: f32,
+    pub amplitude: f32,
+    pub frequency: f32,
+    pub speed: f32,
+    pub foam_width: f32,
+}
+
+impl geng::LoadAsset for Water {
+    fn load(geng: &Rc<Geng>, path: &str) -> geng::AssetFuture<Self> {
+        let json = <String as geng::LoadAsset>::load(geng, path);
+        async move { Ok(serde_json::from_str(&json.await?)?) }.boxed_local()
+    }
+    const DEFAULT_EXT: Option<&'static str> = Some("json");
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sound(json: &str) -> Sound {
+        serde_json::from_str(json).unwrap()
+    }
+
+    #[test]
+    fn pitch_variants() {
+        let event = sound(
+            r#"{"bus":"Sfx","samples":["a.wav","b.wav"],"panned":true,"pitch":[0.94,1.06],
+                "volume":[1.0,1.0],"cooldown":0.0,"max_voices":1,"duration":1.0}"#,
+        );
+        event.validate("sounds.json").unwrap();
+        assert_eq!(
+            event.variants(),
+            [
+                "a_p94.wav",
+                "a.wav",
+                "a_p106.wav",
+                "b_p94.wav",
+                "b.wav",
+                "b_p106.wav"
+            ]
+        );
+    }
+
+    #[test]
+    fn unpanned_pitch_variants() {
+        let event = sound(
+            r#"{"bus":"Sfx","samples":["a.wav"],"pitch":[0.94,1.06],
+                "volume":[1.0,1.0],"cooldown":0.0,"max_voices":1,"duration":1.0}"#,
+        );
+        event.validate("sounds.json").unwrap();
+        assert_eq!(event.variants(), ["a_p94.wav", "a.wav", "a_p106.wav"]);
+    }
+}
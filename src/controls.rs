@@ -0,0 +1,358 @@
+use super::*;
+
+pub const SLOTS: usize = 2;
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum Action {
+    SteerLeft,
+    SteerRight,
+    Start,
+    Restart,
+    Pause,
+    Jump,
+    Slide,
+    MenuUp,
+    MenuDown,
+    MenuLeft,
+    MenuRight,
+    Confirm,
+    Back,
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+enum Group {
+    Game,
+    Menu,
+}
+
+impl Action {
+    pub const ALL: [Self; 13] = [
+        Self::SteerLeft,
+        Self::SteerRight,
+        Self::Start,
+        Self::Restart,
+        Self::Pause,
+        Self::Jump,
+        Self::Slide,
+        Self::MenuUp,
+        Self::MenuDown,
+        Self::MenuLeft,
+        Self::MenuRight,
+        Self::Confirm,
+        Self::Back,
+    ];
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::SteerLeft => translate("left"),
+            Self::SteerRight => translate("right"),
+            Self::Start => translate("start"),
+            Self::Restart => translate("title screen"),
+            Self::Pause => translate("pause"),
+            Self::Jump => translate("jump"),
+            Self::Slide => translate("slide"),
+            Self::MenuUp => translate("menu up"),
+            Self::MenuDown => translate("menu down"),
+            Self::MenuLeft => translate("menu left"),
+            Self::MenuRight => translate("menu right"),
+            Self::Confirm => translate("confirm"),
+            Self::Back => translate("back"),
+        }
+    }
+    fn group(self) -> Group {
+        match self {
+            Self::SteerLeft
+            | Self::SteerRight
+            | Self::Start
+            | Self::Restart
+            | Self::Pause
+            | Self::Jump
+            | Self::Slide => Group::Game,
+            Self::MenuUp
+            | Self::MenuDown
+            | Self::MenuLeft
+            | Self::MenuRight
+            | Self::Confirm
+            | Self::Back => Group::Menu,
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(remote = "geng::Key")]
+enum KeyDef {
+    Num0,
+    Num1,
+    Num2,
+    Num3,
+    Num4,
+    Num5,
+    Num6,
+    Num7,
+    Num8,
+    Num9,
+    A,
+    B,
+    C,
+    D,
+    E,
+    F,
+    G,
+    H,
+    I,
+    J,
+    K,
+    L,
+    M,
+    N,
+    O,
+    P,
+    Q,
+    R,
+    S,
+    T,
+    U,
+    V,
+    W,
+    X,
+    Y,
+    Z,
+    Escape,
+    Space,
+    Enter,
+    Backspace,
+    LShift,
+    RShift,
+    LCtrl,
+    RCtrl,
+    LAlt,
+    RAlt,
+    Left,
+    Right,
+    Up,
+    Down,
+    PageUp,
+    PageDown,
+    F1,
+    F2,
+    F3,
+    F4,
+    F5,
+    F6,
+    F7,
+    F8,
+    F9,
+    F10,
+    F11,
+    F12,
+    Unknown,
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct Key(#[serde(with = "KeyDef")] pub geng::Key);
+
+#[derive(Clone, Serialize, Deserialize)]
+#[serde(from = "HashMap<Action, Vec<Key>>")]
+pub struct Bindings(HashMap<Action, Vec<Key>>);
+
+impl Default for Bindings {
+    fn default() -> Self {
+        use geng::Key::*;
+        let bind = |keys: &[geng::Key]| keys.iter().copied().map(Key).collect();
+        Self(hashmap! {
+            Action::SteerLeft => bind(&[Left, A]),
+            Action::SteerRight => bind(&[Right, D]),
+            Action::Start => bind(&[Space, Enter]),
+            Action::Restart => bind(&[R]),
+            Action::Pause => bind(&[Escape, P]),
+            Action::Jump => bind(&[Up, W]),
+            Action::Slide => bind(&[Down, S]),
+            Action::MenuUp => bind(&[Up, W]),
+            Action::MenuDown => bind(&[Down, S]),
+            Action::MenuLeft => bind(&[Left, A]),
+            Action::MenuRight => bind(&[Right, D]),
+            Action::Confirm => bind(&[Enter, Space]),
+            Action::Back => bind(&[Escape, Backspace]),
+        })
+    }
+}
+
+impl From<HashMap<Action, Vec<Key>>> for Bindings {
+    fn from(mut bindings: HashMap<Action, Vec<Key>>) -> Self {
+        for (action, keys) in Self::default().0 {
+            bindings.entry(action).or_insert(keys);
+        }
+        Self(bindings)
+    }
+}
+
+impl Bindings {
+    pub fn keys(&self, action: Action) -> &[Key] {
+        self.0.get(&action).map_or(&[], |keys| keys.as_slice())
+    }
+    pub fn key(&self, action: Action, slot: usize) -> Option<geng::Key> {
+        self.keys(action).get(slot).map(|&Key(key)| key)
+    }
+    pub fn describe(&self, action: Action) -> String {
+        self.keys(action)
+            .iter()
+            .map(|Key(key)| format!("{:?}", key))
+            .collect::<Vec<_>>()
+            .join("/")
+    }
+    pub fn is_pressed(&self, window: &geng::Window, action: Action) -> bool {
+        self.keys(action)
+            .iter()
+            .any(|&Key(key)| window.is_key_pressed(key))
+    }
+    pub fn matches(&self, key: geng::Key, action: Action) -> bool {
+        self.keys(action).contains(&Key(key))
+    }
+    pub fn bind(&mut self, action: Action, slot: usize, key: geng::Key) {
+        for (&other, keys) in &mut self.0 {
+            if other.group() == action.group() {
+                keys.retain(|&bound| bound != Key(key));
+            }
+        }
+        let keys = self.0.entry(action).or_default();
+        if slot < keys.len() {
+            keys[slot] = Key(key);
+        } else {
+            keys.push(Key(key));
+        }
+    }
+}
+
+#[derive(Default)]
+pub struct RebindScreen {
+    selected: usize,
+    slot: usize,
+    waiting: bool,
+    row_height: f32,
+    top: f32,
+    center: f32,
+}
+
+impl RebindScreen {
+    pub fn handle_event(&mut self, bindings: &mut Bindings, event: &geng::Event) -> bool {
+        let len = Action::ALL.len();
+        match *event {
+            geng::Event::KeyDown { key } if self.waiting => {
+                if !bindings.matches(key, Action::Back) {
+                    bindings.bind(Action::ALL[self.selected], self.slot, key);
+                }
+                self.waiting = false;
+            }
+            geng::Event::KeyDown { key } => {
+                if bindings.matches(key, Action::Back) {
+                    return false;
+                } else if bindings.matches(key, Action::MenuUp) {
+                    self.selected = (self.selected + len - 1) % len;
+                } else if bindings.matches(key, Action::MenuDown) {
+                    self.selected = (self.selected + 1) % len;
+                } else if bindings.matches(key, Action::MenuLeft)
+                    || bindings.matches(key, Action::MenuRight)
+                {
+                    self.slot = (self.slot + 1) % SLOTS;
+                } else if bindings.matches(key, Action::Confirm) {
+                    self.waiting = true;
+                }
+            }
+            geng::Event::MouseDown { position, .. } => {
+                let row = ((self.top - position.y as f32) / self.row_height).floor();
+                if row >= 0.0 && (row as usize) < len {
+                    self.selected = row as usize;
+                    self.slot = if (position.x as f32) < self.center {
+                        0
+                    } else {
+                        1
+                    };
+                    self.waiting = true;
+                } else {
+                    return false;
+                }
+            }
+            _ => {}
+        }
+        true
+    }
+    pub fn draw(
+        &mut self,
+        geng: &Geng,
+        font: &geng::Font,
+        framebuffer: &mut ugli::Framebuffer,
+        bindings: &Bindings,
+    ) {
+        let framebuffer_size = framebuffer.size().map(|x| x as f32);
+        geng.draw_2d().quad(
+            framebuffer,
+            AABB::pos_size(vec2(0.0, 0.0), framebuffer_size),
+            Color::rgba(0.8, 0.8, 1.0, 0.8),
+        );
+        let font_size = (framebuffer_size.y * 0.04).min(framebuffer_size.x * 0.025);
+        let mut y = framebuffer_size.y * 0.9;
+        font.draw_aligned(
+            framebuffer,
+            translate("CONTROLS"),
+            vec2(framebuffer_size.x / 2.0, y),
+            0.5,
+            font_size * 2.0,
+            Color::BLACK,
+        );
+        y -= font_size * 2.0;
+        self.top = y + font_size;
+        self.row_height = font_size * 1.2;
+        self.center = framebuffer_size.x * 0.6;
+        let color = |selected: bool| {
+            if selected {
+                Color::rgb(0.0, 0.0, 0.7)
+            } else {
+                Color::rgb(0.1, 0.1, 0.1)
+            }
+        };
+        for (index, &action) in Action::ALL.iter().enumerate() {
+            font.draw_aligned(
+                framebuffer,
+                action.name(),
+                vec2(framebuffer_size.x * 0.4, y),
+                1.0,
+                font_size,
+                color(index == self.selected),
+            );
+            for slot in 0..SLOTS {
+                let selected = index == self.selected && slot == self.slot;
+                let key = if selected && self.waiting {
+                    "...".to_owned()
+                } else {
+                    bindings
+                        .key(action, slot)
+                        .map_or("-".to_owned(), |key| format!("{:?}", key))
+                };
+                font.draw_aligned(
+                    framebuffer,
+                    &key,
+                    vec2(framebuffer_size.x * (0.5 + 0.2 * slot as f32), y),
+                    0.5,
+                    font_size,
+                    color(selected),
+                );
+            }
+            y -= self.row_height;
+        }
+        y -= font_size;
+        font.draw_aligned(
+            framebuffer,
+            &format!(
+                "{}/{} - {}, {} - {}",
+                bindings.describe(Action::Confirm),
+                translate("click"),
+                translate("rebind"),
+                bindings.describe(Action::Back),
+                translate("back"),
+            ),
+            vec2(framebuffer_size.x / 2.0, y),
+            0.5,
+            font_size * 0.8,
+            Color::rgb(0.1, 0.1, 0.1),
+        );
+    }
+}
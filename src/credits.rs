@@ -0,0 +1,56 @@
+use super::*;
+
+pub struct Credits {
+    ctx: Context,
+    ui_controller: geng::ui::Controller,
+    transition: Option<geng::Transition>,
+}
+
+impl Credits {
+    pub fn new(ctx: &Context) -> Self {
+        Self {
+            ctx: ctx.clone(),
+            ui_controller: geng::ui::Controller::new(),
+            transition: None,
+        }
+    }
+}
+
+impl geng::State for Credits {
+    fn draw(&mut self, framebuffer: &mut ugli::Framebuffer) {
+        ugli::clear(framebuffer, Some(Color::rgb(0.8, 0.8, 1.0)), None);
+        let framebuffer_size = framebuffer.size().map(|x| x as f32);
+        let layout = TextLayout::default()
+            .title(translate("TSUNAMI ESCAPE"))
+            .space(1.0)
+            .text(translate("code - kuviman (Vitaly)"))
+            .text(translate("art - mikky_ti (Tanya)"))
+            .text(translate("sound - AT (Sanya)"))
+            .space(2.0)
+            .small(translate("Any click - back"));
+        self.ui_controller.draw(
+            &mut layout.ui(
+                &self.ctx.font,
+                framebuffer_size,
+                AABB::from_corners(framebuffer_size * 0.05, framebuffer_size * 0.95),
+            ),
+            framebuffer,
+        );
+    }
+    fn update(&mut self, _delta_time: f64) {
+        if !self.ctx.gamepads.borrow_mut().poll().is_empty() {
+            self.transition = Some(geng::Transition::Pop);
+        }
+    }
+    fn handle_event(&mut self, event: geng::Event) {
+        if let geng::Event::KeyDown { .. }
+        | geng::Event::MouseDown { .. }
+        | geng::Event::TouchStart { .. } = event
+        {
+            self.transition = Some(geng::Transition::Pop);
+        }
+    }
+    fn transition(&mut self) -> Option<geng::Transition> {
+        self.transition.take()
+    }
+}
@@ -0,0 +1,156 @@
+use super::*;
+
+#[derive(Debug)]
+pub enum AssetError {
+    Decode {
+        path: String,
+        error: image::ImageError,
+    },
+    Frame {
+        path: String,
+        frame: usize,
+        error: image::ImageError,
+    },
+    NoFrames {
+        path: String,
+    },
+    Manifest {
+        path: String,
+        field: &'static str,
+    },
+    Sidecar {
+        path: String,
+        error: serde_json::Error,
+    },
+    NotPositive {
+        path: String,
+        field: &'static str,
+        value: f32,
+    },
+    NotPowerOfTwo {
+        path: String,
+        width: u32,
+        height: u32,
+    },
+    OutOfRange {
+        path: String,
+        field: &'static str,
+        value: f64,
+        range: RangeInclusive<f64>,
+    },
+    Pack {
+        path: String,
+        reason: &'static str,
+    },
+    Translation {
+        path: String,
+        line: usize,
+        reason: &'static str,
+    },
+    Wav {
+        path: String,
+        reason: &'static str,
+    },
+}
+
+impl std::fmt::Display for AssetError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            Self::Decode { path, error } => write!(f, "Failed to decode {:?}: {}", path, error),
+            Self::Frame { path, frame, error } => write!(
+                f,
+                "Failed to decode frame {} of {:?}: {}",
+                frame, path, error
+            ),
+            Self::NoFrames { path } => write!(f, "{:?} has no frames", path),
+            Self::Manifest { path, field } => write!(f, "{:?} lists no {}", path, field),
+            Self::Sidecar { path, error } => write!(f, "Failed to parse {:?}: {}", path, error),
+            Self::NotPositive { path, field, value } => {
+                write!(f, "{:?} has {} {}, expected > 0", path, field, value)
+            }
+            Self::NotPowerOfTwo {
+                path,
+                width,
+                height,
+            } => write!(f, "{:?} is {}x{}, not a power of two", path, width, height),
+            Self::OutOfRange {
+                path,
+                field,
+                value,
+                range,
+            } => write!(
+                f,
+                "{:?} has {} {}, expected {} to {}",
+                path,
+                field,
+                value,
+                range.start(),
+                range.end()
+            ),
+            Self::Pack { path, reason } => write!(f, "Failed to pack {:?}: {}", path, reason),
+            Self::Translation { path, line, reason } => {
+                write!(f, "{:?} line {}: {}", path, line, reason)
+            }
+            Self::Wav { path, reason } => write!(f, "Failed to decode {:?}: {}", path, reason),
+        }
+    }
+}
+
+impl std::error::Error for AssetError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::Decode { error, .. } | Self::Frame { error, .. } => Some(error),
+            Self::Sidecar { error, .. } => Some(error),
+            Self::NoFrames { .. }
+            | Self::Manifest { .. }
+            | Self::NotPositive { .. }
+            | Self::NotPowerOfTwo { .. }
+            | Self::OutOfRange { .. }
+            | Self::Pack { .. }
+            | Self::Translation { .. }
+            | Self::Wav { .. } => None,
+        }
+    }
+}
+
+pub fn positive(path: &str, field: &'static str, value: f32) -> Result<f32, AssetError> {
+    if value > 0.0 {
+        Ok(value)
+    } else {
+        Err(AssetError::NotPositive {
+            path: path.to_owned(),
+            field,
+            value,
+        })
+    }
+}
+
+pub fn in_range(
+    path: &str,
+    field: &'static str,
+    value: f64,
+    range: RangeInclusive<f64>,
+) -> Result<f64, AssetError> {
+    if range.contains(&value) {
+        Ok(value)
+    } else {
+        Err(AssetError::OutOfRange {
+            path: path.to_owned(),
+            field,
+            value,
+            range,
+        })
+    }
+}
+
+pub fn power_of_two(path: &str, width: u32, height: u32) -> Result<(), AssetError> {
+    if width.is_power_of_two() && height.is_power_of_two() {
+        Ok(())
+    } else {
+        Err(AssetError::NotPowerOfTwo {
+            path: path.to_owned(),
+            width,
+            height,
+        })
+    }
+}
@@ -0,0 +1,36 @@
+use super::*;
+
+pub struct ErrorScreen {
+    font: Rc<geng::Font>,
+    message: String,
+    ui_controller: geng::ui::Controller,
+}
+
+impl ErrorScreen {
+    pub fn new(geng: &Rc<Geng>, error: &anyhow::Error) -> Self {
+        Self {
+            font: load_font(geng),
+            message: format!("{:#}", error),
+            ui_controller: geng::ui::Controller::new(),
+        }
+    }
+}
+
+impl geng::State for ErrorScreen {
+    fn draw(&mut self, framebuffer: &mut ugli::Framebuffer) {
+        ugli::clear(framebuffer, Some(Color::rgb(0.8, 0.8, 1.0)), None);
+        let framebuffer_size = framebuffer.size().map(|x| x as f32);
+        let layout = TextLayout::default()
+            .title(translate("Failed to load assets"))
+            .space(1.0)
+            .small(self.message.as_str());
+        self.ui_controller.draw(
+            &mut layout.ui(
+                &self.font,
+                framebuffer_size,
+                AABB::from_corners(framebuffer_size * 0.05, framebuffer_size * 0.95),
+            ),
+            framebuffer,
+        );
+    }
+}
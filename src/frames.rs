@@ -0,0 +1,252 @@
+use super::*;
+
+use config::{Sheet, Sidecar, DEFAULT_FRAME_DURATION};
+use image::RgbaImage;
+
+pub const MAX_TEXTURE_SIZE: u32 = 4096;
+
+pub type Frames = Vec<(RgbaImage, f32)>;
+
+fn decode<'a>(decoder: impl image::AnimationDecoder<'a>, path: &str) -> Result<Frames, AssetError> {
+    let mut frames = Vec::new();
+    for (index, frame) in decoder.into_frames().enumerate() {
+        let frame = frame.map_err(|error| AssetError::Frame {
+            path: path.to_owned(),
+            frame: index,
+            error,
+        })?;
+        let (numer, denom) = frame.delay().numer_denom_ms();
+        let duration = if numer == 0 || denom == 0 {
+            DEFAULT_FRAME_DURATION
+        } else {
+            numer as f32 / denom as f32 / 1000.0
+        };
+        frames.push((frame.into_buffer(), duration));
+    }
+    if frames.is_empty() {
+        return Err(AssetError::NoFrames {
+            path: path.to_owned(),
+        });
+    }
+    Ok(frames)
+}
+
+pub fn decode_animation(path: &str, data: &[u8]) -> Result<Frames, AssetError> {
+    let decode_error = |error| AssetError::Decode {
+        path: path.to_owned(),
+        error,
+    };
+    if path.ends_with(".gif") {
+        decode(
+            image::codecs::gif::GifDecoder::new(data).map_err(decode_error)?,
+            path,
+        )
+    } else {
+        decode(
+            image::codecs::png::PngDecoder::new(data)
+                .map_err(decode_error)?
+                .apng(),
+            path,
+        )
+    }
+}
+
+pub fn decode_image(path: &str, data: &[u8]) -> Result<RgbaImage, AssetError> {
+    Ok(image::load_from_memory(data)
+        .map_err(|error| AssetError::Decode {
+            path: path.to_owned(),
+            error,
+        })?
+        .into_rgba8())
+}
+
+pub fn slice(path: &str, sheet: &Sheet, image: &RgbaImage) -> Result<Frames, AssetError> {
+    sheet.validate(path, image.width(), image.height())?;
+    let columns = sheet.columns(image.width());
+    let frames = (0..sheet.frame_count(image.width(), image.height()) as u32)
+        .map(|index| {
+            let x = index % columns * sheet.frame_width;
+            let y = index / columns * sheet.frame_height;
+            let frame =
+                image::imageops::crop_imm(image, x, y, sheet.frame_width, sheet.frame_height)
+                    .to_image();
+            (frame, sheet.frame_duration())
+        })
+        .collect();
+    Ok(frames)
+}
+
+pub fn prev_pot(n: u32) -> u32 {
+    let mut x = 1;
+    while x * 2 <= n {
+        x *= 2;
+    }
+    x
+}
+
+pub fn pack(
+    path: &str,
+    frames: &[(RgbaImage, f32)],
+    sidecar: Sidecar,
+    image: &str,
+) -> Result<(RgbaImage, Sheet), AssetError> {
+    let error = |reason| AssetError::Pack {
+        path: path.to_owned(),
+        reason,
+    };
+    let (first, duration) = match frames.first() {
+        Some((first, duration)) => (first, *duration),
+        None => {
+            return Err(AssetError::NoFrames {
+                path: path.to_owned(),
+            })
+        }
+    };
+    if frames
+        .iter()
+        .any(|(frame, _)| frame.dimensions() != first.dimensions())
+    {
+        return Err(error("frames differ in size"));
+    }
+    if frames.iter().any(|&(_, other)| other != duration) {
+        return Err(error("frames must share a single delay"));
+    }
+    let (frame_width, frame_height) = (prev_pot(first.width()), prev_pot(first.height()));
+    let count = frames.len() as u32;
+    let columns = (MAX_TEXTURE_SIZE / frame_width).min(count);
+    let rows = count.div_ceil(columns);
+    if rows * frame_height > MAX_TEXTURE_SIZE {
+        return Err(error("frames do not fit in a single sheet"));
+    }
+    let mut packed = RgbaImage::new(columns * frame_width, rows * frame_height);
+    for (index, (frame, _)) in frames.iter().enumerate() {
+        let frame = image::imageops::resize(
+            frame,
+            frame_width,
+            frame_height,
+            image::imageops::FilterType::Triangle,
+        );
+        let index = index as u32;
+        image::imageops::replace(
+            &mut packed,
+            &frame,
+            index % columns * frame_width,
+            index / columns * frame_height,
+        );
+    }
+    let sheet = Sheet {
+        image: image.to_owned(),
+        frame_width,
+        frame_height,
+        frames: Some(frames.len()),
+        frame_duration: Some(duration),
+        sidecar,
+    };
+    sheet.validate(path, packed.width(), packed.height())?;
+    Ok((packed, sheet))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const APNG: &[u8] = include_bytes!("../static/character/fall.png");
+
+    fn encode(format: image::ImageOutputFormat) -> Vec<u8> {
+        let mut data = Vec::new();
+        image::DynamicImage::new_rgba8(4, 4)
+            .write_to(&mut data, format)
+            .unwrap();
+        data
+    }
+
+    #[test]
+    fn apng() {
+        let frames = decode_animation("fall.png", APNG).unwrap();
+        assert!(frames.len() > 1);
+    }
+
+    #[test]
+    fn jpeg() {
+        let data = encode(image::ImageOutputFormat::Jpeg(90));
+        assert!(matches!(
+            decode_animation("photo.png", &data),
+            Err(AssetError::Decode { .. })
+        ));
+    }
+
+    #[test]
+    fn still_png() {
+        let data = encode(image::ImageOutputFormat::Png);
+        assert!(matches!(
+            decode_animation("still.png", &data),
+            Err(AssetError::NoFrames { .. })
+        ));
+    }
+
+    #[test]
+    fn truncated_header() {
+        assert!(matches!(
+            decode_animation("truncated.png", &APNG[..200]),
+            Err(AssetError::Decode { .. })
+        ));
+    }
+
+    #[test]
+    fn truncated_frames() {
+        assert!(matches!(
+            decode_animation("truncated.png", &APNG[..APNG.len() / 2]),
+            Err(AssetError::Frame { frame, .. }) if frame > 0
+        ));
+    }
+
+    #[test]
+    fn pack_and_slice() {
+        let frames: Frames = (0..3)
+            .map(|index| {
+                (
+                    RgbaImage::from_pixel(5, 3, image::Rgba([index, 0, 0, 255])),
+                    0.05,
+                )
+            })
+            .collect();
+        let (image, sheet) = pack("test.png", &frames, default(), "test_sheet.png").unwrap();
+        assert_eq!((sheet.frame_width, sheet.frame_height), (4, 2));
+        let sliced = slice("test_sheet.json", &sheet, &image).unwrap();
+        assert_eq!(sliced.len(), 3);
+        assert_eq!(sliced[2].0.get_pixel(0, 0)[0], 2);
+        assert_eq!(sliced[2].1, 0.05);
+    }
+
+    #[test]
+    fn sheet_frames_must_be_power_of_two() {
+        let sheet = Sheet {
+            image: "sheet.png".to_owned(),
+            frame_width: 6,
+            frame_height: 4,
+            frames: None,
+            frame_duration: None,
+            sidecar: default(),
+        };
+        assert!(matches!(
+            slice("sheet.json", &sheet, &RgbaImage::new(12, 4)),
+            Err(AssetError::NotPowerOfTwo { .. })
+        ));
+    }
+
+    #[test]
+    fn sheet_frame_duration_must_be_positive() {
+        let sheet = Sheet {
+            image: "sheet.png".to_owned(),
+            frame_width: 4,
+            frame_height: 4,
+            frames: None,
+            frame_duration: Some(0.0),
+            sidecar: default(),
+        };
+        assert!(matches!(
+            slice("sheet.json", &sheet, &RgbaImage::new(8, 4)),
+            Err(AssetError::NotPositive { .. })
+        ));
+    }
+}
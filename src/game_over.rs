@@ -0,0 +1,124 @@
+use super::*;
+
+pub struct GameOver {
+    ctx: Context,
+    world: World,
+    touch: touch::Touch,
+    ui_controller: geng::ui::Controller,
+    transition: Option<geng::Transition>,
+}
+
+impl GameOver {
+    pub fn new(ctx: &Context, mut world: World) -> Self {
+        world.stop_audio();
+        ctx.audio
+            .borrow_mut()
+            .trigger(&ctx.assets().sounds, "game_over");
+        Self {
+            ctx: ctx.clone(),
+            world,
+            touch: default(),
+            ui_controller: geng::ui::Controller::new(),
+            transition: None,
+        }
+    }
+    fn restart(&mut self) {
+        self.transition = Some(geng::Transition::Switch(Box::new(Playing::new(
+            &self.ctx,
+            World::new(&self.ctx),
+        ))));
+    }
+    fn quit(&mut self) {
+        self.transition = Some(geng::Transition::Switch(Box::new(TitleScreen::new(
+            &self.ctx,
+        ))));
+    }
+}
+
+impl geng::State for GameOver {
+    fn draw(&mut self, framebuffer: &mut ugli::Framebuffer) {
+        self.world.draw(framebuffer);
+        let framebuffer_size = framebuffer.size().map(|x| x as f32);
+        self.ctx.geng.draw_2d().quad(
+            framebuffer,
+            AABB::pos_size(vec2(0.0, 0.0), framebuffer_size),
+            Color::rgba(0.8, 0.8, 1.0, 0.5),
+        );
+        let layout = TextLayout::default()
+            .title(translate("THE CITY is UNDER WATER"))
+            .text(translate("And so are you!"))
+            .space(1.0)
+            .paragraph(
+                translate("You survived for"),
+                2.0,
+                Color::rgb(0.1, 0.1, 0.1),
+            )
+            .paragraph(
+                translate("a whole {} seconds!")
+                    .replace("{}", &format!("{:.1}", self.world.time.unwrap_or(0.0))),
+                2.0,
+                Color::rgb(0.1, 0.1, 0.1),
+            )
+            .text(translate("Wow!"))
+            .text(translate("Congratulations!"))
+            .text(translate("Well done!"))
+            .space(2.0)
+            .text(translate("Any click - restart"))
+            .text(format!(
+                "{} - {}",
+                self.ctx.bindings().describe(controls::Action::Restart),
+                translate("title screen"),
+            ));
+        self.ui_controller.draw(
+            &mut layout.ui(
+                &self.ctx.font,
+                framebuffer_size,
+                AABB::from_corners(framebuffer_size * 0.05, framebuffer_size * 0.95),
+            ),
+            framebuffer,
+        );
+    }
+    fn update(&mut self, delta_time: f64) {
+        let events = self.ctx.gamepads.borrow_mut().poll();
+        for event in events {
+            match event {
+                gamepad::Event::Start => self.restart(),
+                gamepad::Event::Restart | gamepad::Event::Pause => self.quit(),
+            }
+        }
+        self.touch.update(delta_time as f32);
+        self.world.update(delta_time as f32, 0.0);
+    }
+    fn handle_event(&mut self, event: geng::Event) {
+        match event {
+            geng::Event::KeyDown { key }
+                if self.ctx.bindings().matches(key, controls::Action::Restart) =>
+            {
+                self.quit();
+            }
+            geng::Event::KeyDown { key }
+                if self.ctx.bindings().matches(key, controls::Action::Start) =>
+            {
+                self.restart();
+            }
+            geng::Event::MouseDown { .. } => {
+                self.restart();
+            }
+            _ => {}
+        }
+        let gesture = self.touch.handle_event(
+            &self.ctx.settings.borrow().touch,
+            &event,
+            self.ctx.geng.window().size().map(|x| x as f32),
+            0.0,
+        );
+        match gesture {
+            Some(touch::Gesture::Tap) => self.restart(),
+            Some(touch::Gesture::SwipeDown) => self.quit(),
+            _ => {}
+        }
+    }
+    fn transition(&mut self) -> Option<geng::Transition> {
+        self.transition.take()
+    }
+}
@@ -0,0 +1,82 @@
+use super::*;
+
+#[derive(Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Settings {
+    pub dead_zone: f64,
+    pub sensitivity: f64,
+}
+
+impl Default for Settings {
+    fn default() -> Self {
+        Self {
+            dead_zone: 0.15,
+            sensitivity: 1.0,
+        }
+    }
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Event {
+    Start,
+    Restart,
+    Pause,
+}
+
+pub struct Gamepads {
+    gilrs: Option<gilrs::Gilrs>,
+}
+
+impl Default for Gamepads {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Gamepads {
+    pub fn new() -> Self {
+        Self {
+            gilrs: match gilrs::Gilrs::new() {
+                Ok(gilrs) => Some(gilrs),
+                Err(e) => {
+                    warn!("Failed to initialize gamepads: {}", e);
+                    None
+                }
+            },
+        }
+    }
+    pub fn poll(&mut self) -> Vec<Event> {
+        let mut events = Vec::new();
+        if let Some(gilrs) = &mut self.gilrs {
+            while let Some(gilrs::Event { event, .. }) = gilrs.next_event() {
+                if let gilrs::EventType::ButtonPressed(button, _) = event {
+                    match button {
+                        gilrs::Button::South | gilrs::Button::East => events.push(Event::Start),
+                        gilrs::Button::Start => events.push(Event::Pause),
+                        gilrs::Button::North | gilrs::Button::Select => events.push(Event::Restart),
+                        _ => {}
+                    }
+                }
+            }
+        }
+        events
+    }
+    pub fn steering(&self, settings: &Settings) -> Option<f32> {
+        let gilrs = self.gilrs.as_ref()?;
+        for (_, gamepad) in gilrs.gamepads() {
+            if gamepad.is_pressed(gilrs::Button::DPadLeft) {
+                return Some(-1.0);
+            }
+            if gamepad.is_pressed(gilrs::Button::DPadRight) {
+                return Some(1.0);
+            }
+            let value = gamepad.value(gilrs::Axis::LeftStickX) as f64;
+            if value.abs() > settings.dead_zone {
+                let value = value.signum() * (value.abs() - settings.dead_zone)
+                    / (1.0 - settings.dead_zone);
+                return Some(clamp_abs(value * settings.sensitivity, 1.0) as f32);
+            }
+        }
+        None
+    }
+}
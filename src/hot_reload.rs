@@ -0,0 +1,121 @@
+use super::*;
+
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
+
+const SCAN_INTERVAL: f64 = 1.0;
+
+fn source_dir() -> PathBuf {
+    Path::new("..").join(tsunami_run::pipeline::SOURCE_DIR)
+}
+
+fn scan(dir: &Path, stamps: &mut HashMap<PathBuf, SystemTime>) {
+    let entries = match std::fs::read_dir(dir) {
+        Ok(entries) => entries,
+        Err(_) => return,
+    };
+    for entry in entries.flatten() {
+        let path = entry.path();
+        if path.is_dir() {
+            scan(&path, stamps);
+        } else if let Ok(modified) = entry.metadata().and_then(|metadata| metadata.modified()) {
+            stamps.insert(path, modified);
+        }
+    }
+}
+
+pub struct HotReload {
+    geng: Rc<Geng>,
+    ctx: Context,
+    states: geng::StateManager,
+    stamps: HashMap<PathBuf, SystemTime>,
+    source_stamps: HashMap<PathBuf, SystemTime>,
+    next_scan: f64,
+    loading: Option<geng::AssetFuture<Assets>>,
+}
+
+impl HotReload {
+    pub fn new(geng: &Rc<Geng>, ctx: &Context, state: Box<dyn geng::State>) -> Self {
+        let mut states = geng::StateManager::new();
+        states.push(state);
+        let mut stamps = HashMap::new();
+        scan(Path::new("."), &mut stamps);
+        let mut source_stamps = HashMap::new();
+        scan(&source_dir(), &mut source_stamps);
+        Self {
+            geng: geng.clone(),
+            ctx: ctx.clone(),
+            states,
+            stamps,
+            source_stamps,
+            next_scan: SCAN_INTERVAL,
+            loading: None,
+        }
+    }
+    fn watch(&mut self, delta_time: f64) {
+        self.next_scan -= delta_time;
+        if self.next_scan > 0.0 || self.loading.is_some() {
+            return;
+        }
+        self.next_scan = SCAN_INTERVAL;
+        let mut source_stamps = HashMap::new();
+        scan(&source_dir(), &mut source_stamps);
+        if source_stamps != self.source_stamps {
+            self.source_stamps = source_stamps;
+            match tsunami_run::pipeline::stale(&source_dir(), Path::new(".")) {
+                Ok(Some(reason)) => {
+                    warn!("{}, run `cargo run --bin build_assets -- --write`", reason)
+                }
+                Ok(None) => {}
+                Err(e) => warn!("Failed to check generated assets: {:#}", e),
+            }
+        }
+        let mut stamps = HashMap::new();
+        scan(Path::new("."), &mut stamps);
+        if stamps != self.stamps {
+            info!("Assets changed, reloading");
+            self.stamps = stamps;
+            self.loading = Some(<Assets as geng::LoadAsset>::load(&self.geng, "."));
+        }
+    }
+    fn poll(&mut self) {
+        let loading = match &mut self.loading {
+            Some(loading) => loading,
+            None => return,
+        };
+        let result = match loading.as_mut().poll(&mut std::task::Context::from_waker(
+            futures::task::noop_waker_ref(),
+        )) {
+            std::task::Poll::Ready(result) => result,
+            std::task::Poll::Pending => return,
+        };
+        self.loading = None;
+        match result {
+            Ok(mut assets) => match prepare_assets(&mut assets) {
+                Ok(()) => {
+                    *self.ctx.assets.borrow_mut() = Rc::new(assets);
+                    info!("Assets reloaded");
+                }
+                Err(e) => error!("Failed to reload assets: {}", e),
+            },
+            Err(e) => error!("Failed to reload assets: {:#}", e),
+        }
+    }
+}
+
+impl geng::State for HotReload {
+    fn update(&mut self, delta_time: f64) {
+        self.watch(delta_time);
+        self.poll();
+        self.states.update(delta_time);
+    }
+    fn draw(&mut self, framebuffer: &mut ugli::Framebuffer) {
+        self.states.draw(framebuffer);
+    }
+    fn handle_event(&mut self, event: geng::Event) {
+        self.states.handle_event(event);
+    }
+    fn transition(&mut self) -> Option<geng::Transition> {
+        self.states.transition()
+    }
+}
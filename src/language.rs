@@ -0,0 +1,39 @@
+use super::*;
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Serialize, Deserialize, Default)]
+pub enum Language {
+    #[default]
+    Auto,
+    English,
+    Russian,
+}
+
+fn detect_locale() -> String {
+    #[cfg(target_arch = "wasm32")]
+    let locale = web_sys::window().unwrap().navigator().language();
+    #[cfg(not(target_arch = "wasm32"))]
+    let locale = ["LC_ALL", "LC_MESSAGES", "LANG"]
+        .iter()
+        .find_map(|var| std::env::var(var).ok().filter(|value| !value.is_empty()));
+    let mut locale = locale.unwrap_or_else(|| "en".to_owned());
+    locale.truncate(2);
+    locale.to_lowercase()
+}
+
+impl Language {
+    pub const ALL: [Self; 3] = [Self::Auto, Self::English, Self::Russian];
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Auto => translate("auto"),
+            Self::English => "English",
+            Self::Russian => "Русский",
+        }
+    }
+    pub fn apply(self) {
+        match self {
+            Self::Auto => set_locale(&detect_locale()),
+            Self::English => set_locale("en"),
+            Self::Russian => set_locale("ru"),
+        }
+    }
+}
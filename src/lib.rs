@@ -0,0 +1,23 @@
+use geng::prelude::*;
+
+pub mod config;
+mod error;
+pub mod frames;
+pub mod pipeline;
+pub mod wav;
+
+pub use error::{in_range, positive, power_of_two, AssetError};
+
+pub fn relative(base: &str, path: &str) -> String {
+    match base.rfind('/') {
+        Some(index) => format!("{}/{}", &base[..index], path),
+        None => path.to_owned(),
+    }
+}
+
+pub fn sidecar_path(path: &str) -> String {
+    match path.rsplit_once('.') {
+        Some((stem, _)) => format!("{}.json", stem),
+        None => format!("{}.json", path),
+    }
+}
@@ -0,0 +1,293 @@
+use super::*;
+
+const CYCLE_LENGTH: f32 = 100.0;
+const RAINDROPS: usize = 300;
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum TimeOfDay {
+    Dawn,
+    Noon,
+    Sunset,
+    StormNight,
+}
+
+impl TimeOfDay {
+    pub const ALL: [Self; 4] = [Self::Dawn, Self::Noon, Self::Sunset, Self::StormNight];
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Dawn => translate("dawn"),
+            Self::Noon => translate("noon"),
+            Self::Sunset => translate("sunset"),
+            Self::StormNight => translate("storm night"),
+        }
+    }
+    pub fn palette(self) -> Palette {
+        match self {
+            Self::Dawn => Palette {
+                sky: Color::rgb(1.0, 0.75, 0.6),
+                sea: Color::rgb(0.2, 0.3, 0.8),
+                sand: Color::rgb(0.95, 0.85, 0.4),
+                grass: Color::rgb(0.2, 0.6, 0.2),
+                tint: Color::rgb(1.0, 0.9, 0.85),
+                rain: 0.0,
+            },
+            Self::Noon => Palette {
+                sky: Color::rgb(0.8, 0.8, 1.0),
+                sea: Color::rgb(0.0, 0.0, 1.0),
+                sand: Color::rgb(1.0, 1.0, 0.0),
+                grass: Color::rgb(0.0, 0.7, 0.0),
+                tint: Color::WHITE,
+                rain: 0.0,
+            },
+            Self::Sunset => Palette {
+                sky: Color::rgb(1.0, 0.55, 0.3),
+                sea: Color::rgb(0.3, 0.2, 0.6),
+                sand: Color::rgb(0.9, 0.6, 0.2),
+                grass: Color::rgb(0.3, 0.45, 0.1),
+                tint: Color::rgb(1.0, 0.75, 0.6),
+                rain: 0.0,
+            },
+            Self::StormNight => Palette {
+                sky: Color::rgb(0.1, 0.1, 0.2),
+                sea: Color::rgb(0.0, 0.0, 0.3),
+                sand: Color::rgb(0.35, 0.35, 0.2),
+                grass: Color::rgb(0.0, 0.2, 0.05),
+                tint: Color::rgb(0.4, 0.4, 0.55),
+                rain: 1.0,
+            },
+        }
+    }
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum TimeOfDaySetting {
+    Random,
+    Cycle,
+    Fixed(TimeOfDay),
+}
+
+impl TimeOfDaySetting {
+    pub const ALL: [Self; 6] = [
+        Self::Random,
+        Self::Cycle,
+        Self::Fixed(TimeOfDay::Dawn),
+        Self::Fixed(TimeOfDay::Noon),
+        Self::Fixed(TimeOfDay::Sunset),
+        Self::Fixed(TimeOfDay::StormNight),
+    ];
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Random => translate("random"),
+            Self::Cycle => translate("cycle"),
+            Self::Fixed(time_of_day) => time_of_day.name(),
+        }
+    }
+}
+
+impl Default for TimeOfDaySetting {
+    fn default() -> Self {
+        Self::Fixed(TimeOfDay::Noon)
+    }
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Serialize, Deserialize, Default)]
+pub enum Weather {
+    #[default]
+    Auto,
+    Clear,
+    Rain,
+}
+
+impl Weather {
+    pub const ALL: [Self; 3] = [Self::Auto, Self::Clear, Self::Rain];
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Auto => translate("auto"),
+            Self::Clear => translate("clear"),
+            Self::Rain => translate("rain"),
+        }
+    }
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Serialize, Deserialize, Default)]
+pub enum ColorBlindMode {
+    #[default]
+    Off,
+    Protanopia,
+    Deuteranopia,
+    Tritanopia,
+}
+
+impl ColorBlindMode {
+    pub const ALL: [Self; 4] = [
+        Self::Off,
+        Self::Protanopia,
+        Self::Deuteranopia,
+        Self::Tritanopia,
+    ];
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Off => translate("off"),
+            Self::Protanopia => translate("protanopia"),
+            Self::Deuteranopia => translate("deuteranopia"),
+            Self::Tritanopia => translate("tritanopia"),
+        }
+    }
+    fn correct(self, color: Color<f32>) -> Color<f32> {
+        let simulation = match self {
+            Self::Off => return color,
+            Self::Protanopia => [
+                [0.567, 0.433, 0.0],
+                [0.558, 0.442, 0.0],
+                [0.0, 0.242, 0.758],
+            ],
+            Self::Deuteranopia => [[0.625, 0.375, 0.0], [0.7, 0.3, 0.0], [0.0, 0.3, 0.7]],
+            Self::Tritanopia => [[0.95, 0.05, 0.0], [0.0, 0.433, 0.567], [0.0, 0.475, 0.525]],
+        };
+        let rgb = [color.r, color.g, color.b];
+        let simulated: Vec<f32> = simulation
+            .iter()
+            .map(|row| row.iter().zip(&rgb).map(|(a, b)| a * b).sum())
+            .collect();
+        let error_r = color.r - simulated[0];
+        let error_g = color.g - simulated[1];
+        let error_b = color.b - simulated[2];
+        Color::rgba(
+            color.r,
+            clamp(color.g + 0.7 * error_r + error_g, 0.0..=1.0),
+            clamp(color.b + 0.7 * error_r + error_b, 0.0..=1.0),
+            color.a,
+        )
+    }
+}
+
+#[derive(Debug, Copy, Clone)]
+pub struct Palette {
+    pub sky: Color<f32>,
+    pub sea: Color<f32>,
+    pub sand: Color<f32>,
+    pub grass: Color<f32>,
+    pub tint: Color<f32>,
+    pub rain: f32,
+}
+
+fn mix(a: Color<f32>, b: Color<f32>, t: f32) -> Color<f32> {
+    Color::rgba(
+        a.r + (b.r - a.r) * t,
+        a.g + (b.g - a.g) * t,
+        a.b + (b.b - a.b) * t,
+        a.a + (b.a - a.a) * t,
+    )
+}
+
+impl Palette {
+    fn mix(&self, other: &Self, t: f32) -> Self {
+        Self {
+            sky: mix(self.sky, other.sky, t),
+            sea: mix(self.sea, other.sea, t),
+            sand: mix(self.sand, other.sand, t),
+            grass: mix(self.grass, other.grass, t),
+            tint: mix(self.tint, other.tint, t),
+            rain: self.rain + (other.rain - self.rain) * t,
+        }
+    }
+    pub fn color_blind(self, mode: ColorBlindMode) -> Self {
+        Self {
+            sky: mode.correct(self.sky),
+            sea: mode.correct(self.sea),
+            sand: mode.correct(self.sand),
+            grass: mode.correct(self.grass),
+            tint: mode.correct(self.tint),
+            rain: self.rain,
+        }
+    }
+    pub fn tinted(&self, color: Color<f32>) -> Color<f32> {
+        Color::rgba(
+            color.r * self.tint.r,
+            color.g * self.tint.g,
+            color.b * self.tint.b,
+            color.a * self.tint.a,
+        )
+    }
+}
+
+pub struct Lighting {
+    time_of_day: Option<TimeOfDay>,
+    weather: Weather,
+    raindrops: Vec<Vec2<f32>>,
+}
+
+impl Lighting {
+    pub fn new(time_of_day: TimeOfDaySetting, weather: Weather) -> Self {
+        let mut rng = rand::thread_rng();
+        Self {
+            time_of_day: match time_of_day {
+                TimeOfDaySetting::Random => Some(*TimeOfDay::ALL.choose(&mut rng).unwrap()),
+                TimeOfDaySetting::Cycle => None,
+                TimeOfDaySetting::Fixed(time_of_day) => Some(time_of_day),
+            },
+            weather,
+            raindrops: (0..RAINDROPS)
+                .map(|_| vec2(rng.gen_range(0.0..1.0), rng.gen_range(0.0..1.0)))
+                .collect(),
+        }
+    }
+    pub fn palette(&self, distance: f32) -> Palette {
+        let mut palette = match self.time_of_day {
+            Some(time_of_day) => time_of_day.palette(),
+            None => {
+                let phase = (distance.max(0.0) / CYCLE_LENGTH) % TimeOfDay::ALL.len() as f32;
+                let index = phase as usize;
+                TimeOfDay::ALL[index].palette().mix(
+                    &TimeOfDay::ALL[(index + 1) % TimeOfDay::ALL.len()].palette(),
+                    phase - index as f32,
+                )
+            }
+        };
+        match self.weather {
+            Weather::Auto => {}
+            Weather::Clear => palette.rain = 0.0,
+            Weather::Rain => palette.rain = 1.0,
+        }
+        palette
+    }
+    pub fn update(&mut self, delta_time: f32) {
+        for drop in &mut self.raindrops {
+            drop.y -= 2.0 * delta_time;
+            drop.x -= 0.2 * delta_time;
+            if drop.y < 0.0 {
+                drop.y += 1.0;
+                drop.x = rand::thread_rng().gen_range(0.0..1.0);
+            }
+            if drop.x < 0.0 {
+                drop.x += 1.0;
+            }
+        }
+    }
+    pub fn draw_rain(&self, geng: &Geng, framebuffer: &mut ugli::Framebuffer, palette: &Palette) {
+        let framebuffer_size = framebuffer.size().map(|x| x as f32);
+        let count = (self.raindrops.len() as f32 * palette.rain) as usize;
+        let size = vec2(1.0, framebuffer_size.y * 0.03);
+        let vertices: Vec<Vec2<f32>> = self.raindrops[..count]
+            .iter()
+            .flat_map(|drop| {
+                let position = vec2(drop.x * framebuffer_size.x, drop.y * framebuffer_size.y);
+                let corners = [
+                    position,
+                    position + vec2(size.x, 0.0),
+                    position + size,
+                    position + vec2(0.0, size.y),
+                ];
+                [0, 1, 2, 0, 2, 3].map(|index| corners[index])
+            })
+            .collect();
+        if !vertices.is_empty() {
+            geng.draw_2d().draw(
+                framebuffer,
+                &vertices,
+                Color::rgba(0.7, 0.7, 0.9, 0.5),
+                ugli::DrawMode::Triangles,
+            );
+        }
+    }
+}
@@ -0,0 +1,73 @@
+use super::*;
+
+#[derive(Default)]
+pub struct Menu {
+    selected: usize,
+    row_height: f32,
+    top: f32,
+}
+
+impl Menu {
+    fn row(&self, position: Vec2<f64>, len: usize) -> Option<usize> {
+        let row = ((self.top - position.y as f32) / self.row_height).floor();
+        if row >= 0.0 && (row as usize) < len {
+            Some(row as usize)
+        } else {
+            None
+        }
+    }
+    pub fn handle_event(
+        &mut self,
+        bindings: &controls::Bindings,
+        event: &geng::Event,
+        len: usize,
+    ) -> Option<usize> {
+        match *event {
+            geng::Event::KeyDown { key } => {
+                if bindings.matches(key, controls::Action::MenuUp) {
+                    self.selected = (self.selected + len - 1) % len;
+                } else if bindings.matches(key, controls::Action::MenuDown) {
+                    self.selected = (self.selected + 1) % len;
+                } else if bindings.matches(key, controls::Action::Confirm) {
+                    return Some(self.selected);
+                }
+            }
+            geng::Event::MouseMove { position, .. } => {
+                if let Some(row) = self.row(position, len) {
+                    self.selected = row;
+                }
+            }
+            geng::Event::MouseDown { position, .. } => return self.row(position, len),
+            geng::Event::TouchStart { ref touches } => return self.row(touches[0].position, len),
+            _ => {}
+        }
+        None
+    }
+    pub fn draw(
+        &mut self,
+        font: &geng::Font,
+        framebuffer: &mut ugli::Framebuffer,
+        items: &[&str],
+        mut y: f32,
+        font_size: f32,
+    ) {
+        let framebuffer_size = framebuffer.size().map(|x| x as f32);
+        self.row_height = font_size * 1.5;
+        self.top = y + self.row_height * 0.75;
+        for (index, item) in items.iter().enumerate() {
+            font.draw_aligned(
+                framebuffer,
+                item,
+                vec2(framebuffer_size.x / 2.0, y),
+                0.5,
+                font_size * 1.2,
+                if index == self.selected {
+                    Color::rgb(0.0, 0.0, 0.7)
+                } else {
+                    Color::rgb(0.1, 0.1, 0.1)
+                },
+            );
+            y -= self.row_height;
+        }
+    }
+}
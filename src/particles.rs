@@ -0,0 +1,121 @@
+use super::*;
+
+const GRAVITY: f32 = 3.0;
+
+pub struct Particle {
+    pub position: Vec3<f32>,
+    velocity: Vec3<f32>,
+    pub size: f32,
+    color: Color<f32>,
+    time: f32,
+    lifetime: f32,
+}
+
+impl Particle {
+    pub fn color(&self) -> Color<f32> {
+        let mut color = self.color;
+        color.a *= 1.0 - self.time / self.lifetime;
+        color
+    }
+}
+
+#[derive(Default)]
+pub struct Particles {
+    particles: Vec<Particle>,
+    spray_timer: f32,
+}
+
+impl Particles {
+    fn spawn(
+        &mut self,
+        position: Vec3<f32>,
+        velocity: Vec3<f32>,
+        size: f32,
+        color: Color<f32>,
+        lifetime: f32,
+    ) {
+        self.particles.push(Particle {
+            position,
+            velocity,
+            size,
+            color,
+            time: 0.0,
+            lifetime,
+        });
+    }
+    pub fn spray(&mut self, tsunami_position: f32, delta_time: f32) {
+        const RATE: f32 = 60.0;
+        self.spray_timer += delta_time * RATE;
+        let mut rng = rand::thread_rng();
+        while self.spray_timer >= 1.0 {
+            self.spray_timer -= 1.0;
+            self.spawn(
+                vec3(
+                    rng.gen_range(-2.0..2.0),
+                    tsunami_position + rng.gen_range(0.0..0.2),
+                    rng.gen_range(0.9..1.3),
+                ),
+                vec3(
+                    rng.gen_range(-0.2..0.2),
+                    rng.gen_range(1.0..2.0),
+                    rng.gen_range(0.5..1.5),
+                ),
+                rng.gen_range(0.02..0.05),
+                Color::rgba(0.9, 0.95, 1.0, 0.8),
+                rng.gen_range(0.3..0.7),
+            );
+        }
+    }
+    pub fn dust(&mut self, position: Vec2<f32>) {
+        let mut rng = rand::thread_rng();
+        for _ in 0..10 {
+            let angle = rng.gen_range(0.0..2.0 * std::f32::consts::PI);
+            let speed = rng.gen_range(0.1..0.3);
+            self.spawn(
+                position.extend(0.02),
+                vec3(
+                    angle.cos() * speed,
+                    angle.sin() * speed,
+                    rng.gen_range(0.2..0.5),
+                ),
+                rng.gen_range(0.02..0.04),
+                Color::rgba(0.6, 0.55, 0.45, 0.7),
+                rng.gen_range(0.4..0.8),
+            );
+        }
+    }
+    pub fn sparks(&mut self, position: Vec2<f32>) {
+        let mut rng = rand::thread_rng();
+        for _ in 0..15 {
+            let angle = rng.gen_range(0.0..2.0 * std::f32::consts::PI);
+            let speed = rng.gen_range(0.5..1.0);
+            self.spawn(
+                position.extend(0.1),
+                vec3(
+                    angle.cos() * speed,
+                    angle.sin() * speed,
+                    rng.gen_range(0.5..1.0),
+                ),
+                rng.gen_range(0.01..0.02),
+                Color::rgb(1.0, 0.8, 0.2),
+                rng.gen_range(0.2..0.4),
+            );
+        }
+    }
+    pub fn update(&mut self, delta_time: f32) {
+        for particle in &mut self.particles {
+            particle.time += delta_time;
+            particle.velocity.z -= GRAVITY * delta_time;
+            particle.position += particle.velocity * delta_time;
+            if particle.position.z < 0.0 {
+                particle.position.z = 0.0;
+                particle.velocity = vec3(0.0, 0.0, 0.0);
+            }
+        }
+        self.particles
+            .retain(|particle| particle.time < particle.lifetime);
+    }
+    pub fn iter(&self) -> impl Iterator<Item = &Particle> {
+        self.particles.iter()
+    }
+}
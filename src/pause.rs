@@ -0,0 +1,79 @@
+use super::*;
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Item {
+    Resume,
+    Restart,
+    Settings,
+    Quit,
+}
+
+impl Item {
+    pub const ALL: [Self; 4] = [Self::Resume, Self::Restart, Self::Settings, Self::Quit];
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Resume => translate("resume"),
+            Self::Restart => translate("restart"),
+            Self::Settings => translate("settings"),
+            Self::Quit => translate("quit"),
+        }
+    }
+}
+
+/// Auto-pause on focus loss is web-only: geng 0.8 drops window focus events on
+/// native, so there this always returns `true` and `Playing::update` only
+/// pauses after a long frame stall, not when the window loses focus.
+pub fn window_focused() -> bool {
+    #[cfg(target_arch = "wasm32")]
+    {
+        web_sys::window()
+            .and_then(|window| window.document())
+            .map_or(true, |document| document.has_focus().unwrap_or(true))
+    }
+    #[cfg(not(target_arch = "wasm32"))]
+    {
+        true
+    }
+}
+
+#[derive(Default)]
+pub struct PauseMenu {
+    menu: menu::Menu,
+}
+
+impl PauseMenu {
+    pub fn handle_event(
+        &mut self,
+        bindings: &controls::Bindings,
+        event: &geng::Event,
+    ) -> Option<Item> {
+        match event {
+            geng::Event::TouchStart { touches } if touches.len() >= 2 => Some(Item::Resume),
+            _ => self
+                .menu
+                .handle_event(bindings, event, Item::ALL.len())
+                .map(|index| Item::ALL[index]),
+        }
+    }
+    pub fn draw(&mut self, geng: &Geng, font: &geng::Font, framebuffer: &mut ugli::Framebuffer) {
+        let framebuffer_size = framebuffer.size().map(|x| x as f32);
+        geng.draw_2d().quad(
+            framebuffer,
+            AABB::pos_size(vec2(0.0, 0.0), framebuffer_size),
+            Color::rgba(0.8, 0.8, 1.0, 0.8),
+        );
+        let font_size = (framebuffer_size.y * 0.05).min(framebuffer_size.x * 0.03);
+        let y = framebuffer_size.y * 0.7;
+        font.draw_aligned(
+            framebuffer,
+            translate("PAUSE"),
+            vec2(framebuffer_size.x / 2.0, y),
+            0.5,
+            font_size * 2.0,
+            Color::BLACK,
+        );
+        let items: Vec<&str> = Item::ALL.iter().map(|item| item.name()).collect();
+        self.menu
+            .draw(font, framebuffer, &items, y - font_size * 2.5, font_size);
+    }
+}
@@ -0,0 +1,113 @@
+use super::*;
+
+use image::RgbaImage;
+use std::path::Path;
+
+pub const SOURCE_DIR: &str = "assets";
+pub const TSUNAMI: &str = "tsunami.png";
+pub const TSUNAMI_SHEET: &str = "tsunami_sheet";
+pub const SOUNDS: &str = "sounds";
+pub const LOOPS: &str = "loops";
+
+fn read(dir: &Path, path: &str) -> anyhow::Result<Vec<u8>> {
+    std::fs::read(dir.join(path)).map_err(|e| anyhow::anyhow!("{}: {}", path, e))
+}
+
+pub fn generate(source_dir: &Path) -> anyhow::Result<(RgbaImage, config::Sheet)> {
+    let frames = frames::decode_animation(TSUNAMI, &read(source_dir, TSUNAMI)?)?;
+    let sidecar_path = sidecar_path(TSUNAMI);
+    let sidecar: config::Sidecar = match read(source_dir, &sidecar_path) {
+        Ok(json) => serde_json::from_slice(&json).map_err(|error| AssetError::Sidecar {
+            path: sidecar_path.clone(),
+            error,
+        })?,
+        Err(_) => default(),
+    };
+    sidecar.validate(&sidecar_path, Some(frames.len()))?;
+    Ok(frames::pack(
+        TSUNAMI,
+        &frames,
+        sidecar,
+        &format!("{}.png", TSUNAMI_SHEET),
+    )?)
+}
+
+fn wav_sources(source_dir: &Path, dir: &str) -> anyhow::Result<Vec<String>> {
+    let mut sources = Vec::new();
+    for entry in std::fs::read_dir(source_dir.join(dir))? {
+        let name = entry?.file_name().to_string_lossy().into_owned();
+        if name.ends_with(".wav") {
+            sources.push(format!("{}/{}", dir, name));
+        }
+    }
+    sources.sort();
+    Ok(sources)
+}
+
+pub fn generate_sounds(source_dir: &Path) -> anyhow::Result<Vec<(String, String, Vec<u8>)>> {
+    let mut outputs = Vec::new();
+    for (dir, pitches) in [(SOUNDS, &wav::PITCHES[..]), (LOOPS, &[100][..])] {
+        for source in wav_sources(source_dir, dir)? {
+            let original = wav::decode(&source, &read(source_dir, &source)?)?;
+            for &pitch in pitches {
+                let [left, right] = wav::pan(&wav::resample(&original, pitch));
+                let [left_path, right_path] = wav::panned_paths(&wav::pitched_path(&source, pitch));
+                outputs.push((source.clone(), left_path, left));
+                outputs.push((source.clone(), right_path, right));
+            }
+        }
+    }
+    Ok(outputs)
+}
+
+pub fn write(source_dir: &Path, output_dir: &Path) -> anyhow::Result<()> {
+    for dir in [SOUNDS, LOOPS] {
+        std::fs::create_dir_all(output_dir.join(dir))?;
+    }
+    for (_, path, data) in generate_sounds(source_dir)? {
+        std::fs::write(output_dir.join(path), data)?;
+    }
+    let (image, sheet) = generate(source_dir)?;
+    image.save(output_dir.join(&sheet.image))?;
+    std::fs::write(
+        output_dir.join(format!("{}.json", TSUNAMI_SHEET)),
+        serde_json::to_string_pretty(&sheet)? + "\n",
+    )?;
+    Ok(())
+}
+
+pub fn stale(source_dir: &Path, output_dir: &Path) -> anyhow::Result<Option<String>> {
+    for (source, path, data) in generate_sounds(source_dir)? {
+        if read(output_dir, &path).ok().as_ref() != Some(&data) {
+            return Ok(Some(format!("{} does not match {}", path, source)));
+        }
+    }
+    let (image, sheet) = generate(source_dir)?;
+    let json_path = format!("{}.json", TSUNAMI_SHEET);
+    let current: Option<config::Sheet> = read(output_dir, &json_path)
+        .ok()
+        .and_then(|json| serde_json::from_slice(&json).ok());
+    if current.as_ref() != Some(&sheet) {
+        return Ok(Some(format!("{} does not match {}", json_path, TSUNAMI)));
+    }
+    let current = read(output_dir, &sheet.image)
+        .ok()
+        .and_then(|data| frames::decode_image(&sheet.image, &data).ok());
+    if current.as_ref() != Some(&image) {
+        return Ok(Some(format!("{} does not match {}", sheet.image, TSUNAMI)));
+    }
+    Ok(None)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn generated_assets_are_up_to_date() {
+        let root = Path::new(env!("CARGO_MANIFEST_DIR"));
+        if let Some(reason) = stale(&root.join(SOURCE_DIR), &root.join("static")).unwrap() {
+            panic!("{}, run `cargo run --bin build_assets -- --write`", reason);
+        }
+    }
+}
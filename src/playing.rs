@@ -0,0 +1,216 @@
+use super::*;
+
+pub struct Playing {
+    ctx: Context,
+    world: Option<World>,
+    pressed_location: Option<f32>,
+    touch: touch::Touch,
+    pause_menu: Option<pause::PauseMenu>,
+    transition: Option<geng::Transition>,
+}
+
+impl Playing {
+    pub fn new(ctx: &Context, mut world: World) -> Self {
+        ctx.tilt.calibrate();
+        world.start();
+        Self {
+            ctx: ctx.clone(),
+            world: Some(world),
+            pressed_location: None,
+            touch: default(),
+            pause_menu: None,
+            transition: None,
+        }
+    }
+    fn restart(&mut self) {
+        self.transition = Some(geng::Transition::Switch(Box::new(Playing::new(
+            &self.ctx,
+            World::new(&self.ctx),
+        ))));
+    }
+    fn quit(&mut self) {
+        self.transition = Some(geng::Transition::Switch(Box::new(TitleScreen::new(
+            &self.ctx,
+        ))));
+    }
+    fn pause(&mut self) {
+        if self.pause_menu.is_none() {
+            self.pause_menu = Some(default());
+            self.pressed_location = None;
+            self.touch.reset();
+            if let Some(world) = &mut self.world {
+                world.pause_audio();
+            }
+        }
+    }
+    fn resume(&mut self) {
+        if self.pause_menu.take().is_some() {
+            if let Some(world) = &mut self.world {
+                world.resume_audio();
+            }
+        }
+    }
+    fn steering(&mut self) -> f32 {
+        let settings = self.ctx.settings.borrow();
+        let window = self.ctx.geng.window();
+        let mut steering = 0.0;
+        if settings
+            .controls
+            .is_pressed(window, controls::Action::SteerLeft)
+        {
+            steering -= 1.0;
+            self.pressed_location = None;
+            self.touch.reset();
+        }
+        if settings
+            .controls
+            .is_pressed(window, controls::Action::SteerRight)
+        {
+            steering += 1.0;
+            self.pressed_location = None;
+            self.touch.reset();
+        }
+        if let Some(value) = self.ctx.gamepads.borrow().steering(&settings.gamepad) {
+            steering = value;
+            self.pressed_location = None;
+            self.touch.reset();
+        }
+        let target = match self.pressed_location {
+            Some(location) => {
+                let window_size = window.size();
+                Some(
+                    (location - window_size.x as f32 / 2.0)
+                        / (min(window_size.x, window_size.y) as f32 / 2.0),
+                )
+            }
+            None => self
+                .touch
+                .target()
+                .or_else(|| self.ctx.tilt.target(&settings.tilt)),
+        };
+        if let (Some(target), Some(world)) = (target, &self.world) {
+            steering = clamp_abs(
+                (target * world.road_ratio - world.player.position.x) * 10.0,
+                1.0,
+            );
+        }
+        steering
+    }
+}
+
+impl geng::State for Playing {
+    fn draw(&mut self, framebuffer: &mut ugli::Framebuffer) {
+        let world = match &mut self.world {
+            Some(world) => world,
+            None => return,
+        };
+        world.draw(framebuffer);
+        let framebuffer_size = framebuffer.size().map(|x| x as f32);
+        let font_size = (framebuffer_size.y * 0.05).min(framebuffer_size.x * 0.03);
+        self.ctx.font.draw_aligned(
+            framebuffer,
+            &format!("{:.1}", world.time.unwrap_or(0.0)),
+            vec2(
+                framebuffer_size.x / 2.0,
+                framebuffer_size.y - font_size - 10.0,
+            ),
+            0.5,
+            font_size,
+            Color::BLACK,
+        );
+        if let Some(menu) = &mut self.pause_menu {
+            menu.draw(&self.ctx.geng, &self.ctx.font, framebuffer);
+        }
+    }
+    fn update(&mut self, delta_time: f64) {
+        let events = self.ctx.gamepads.borrow_mut().poll();
+        for event in events {
+            match event {
+                gamepad::Event::Start | gamepad::Event::Pause if self.pause_menu.is_some() => {
+                    self.resume()
+                }
+                gamepad::Event::Pause => self.pause(),
+                gamepad::Event::Start => {}
+                gamepad::Event::Restart => self.quit(),
+            }
+        }
+        if !pause::window_focused() || delta_time > 0.5 {
+            self.pause();
+        }
+        let delta_time = if self.pause_menu.is_some() {
+            0.0
+        } else {
+            delta_time as f32
+        };
+        self.touch.update(delta_time);
+        let steering = self.steering();
+        let world = match &mut self.world {
+            Some(world) => world,
+            None => return,
+        };
+        world.update(delta_time, steering);
+        if world.game_finished() {
+            if let Some(world) = self.world.take() {
+                self.transition = Some(geng::Transition::Switch(Box::new(GameOver::new(
+                    &self.ctx, world,
+                ))));
+            }
+        }
+    }
+    fn handle_event(&mut self, event: geng::Event) {
+        if let Some(menu) = &mut self.pause_menu {
+            if let geng::Event::KeyDown { key } = event {
+                if self.ctx.bindings().matches(key, controls::Action::Pause) {
+                    self.resume();
+                    return;
+                }
+            }
+            let item = menu.handle_event(&self.ctx.bindings(), &event);
+            match item {
+                Some(pause::Item::Resume) => self.resume(),
+                Some(pause::Item::Restart) => self.restart(),
+                Some(pause::Item::Settings) => {
+                    self.transition = Some(geng::Transition::Push(Box::new(
+                        settings_screen::SettingsScreen::new(&self.ctx),
+                    )))
+                }
+                Some(pause::Item::Quit) => self.quit(),
+                None => {}
+            }
+            return;
+        }
+        match event {
+            geng::Event::MouseDown { position, .. } => {
+                self.pressed_location = Some(position.x as f32);
+            }
+            geng::Event::MouseMove { position, .. } if self.pressed_location.is_some() => {
+                self.pressed_location = Some(position.x as f32);
+            }
+            geng::Event::MouseUp { .. } => {
+                self.pressed_location = None;
+            }
+            _ => {}
+        }
+        let gesture = self.touch.handle_event(
+            &self.ctx.settings.borrow().touch,
+            &event,
+            self.ctx.geng.window().size().map(|x| x as f32),
+            self.world
+                .as_ref()
+                .map_or(0.0, |world| world.player.position.x / world.road_ratio),
+        );
+        if gesture == Some(touch::Gesture::TwoFingerTap) {
+            self.pause();
+        }
+        if let geng::Event::KeyDown { key } = event {
+            if self.ctx.bindings().matches(key, controls::Action::Restart) {
+                self.quit();
+            } else if self.ctx.bindings().matches(key, controls::Action::Pause) {
+                self.pause();
+            }
+        }
+    }
+    fn transition(&mut self) -> Option<geng::Transition> {
+        self.transition.take()
+    }
+}
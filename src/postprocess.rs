@@ -0,0 +1,105 @@
+use super::*;
+
+#[derive(Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Settings {
+    pub enabled: bool,
+    pub vignette: bool,
+    pub motion_blur: bool,
+    pub underwater: bool,
+    pub crt: bool,
+}
+
+impl Default for Settings {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            vignette: true,
+            motion_blur: true,
+            underwater: true,
+            crt: false,
+        }
+    }
+}
+
+pub struct Effects {
+    pub vignette: f32,
+    pub motion_blur: f32,
+    pub underwater: f32,
+}
+
+#[derive(ugli::Vertex, Copy, Clone)]
+struct Vertex {
+    a_pos: Vec2<f32>,
+}
+
+pub struct PostProcessing {
+    geng: Rc<Geng>,
+    shader: Rc<Shader>,
+    quad: ugli::VertexBuffer<Vertex>,
+    texture: Option<ugli::Texture>,
+    time: f32,
+}
+
+impl PostProcessing {
+    pub fn new(geng: &Rc<Geng>, shader: &Rc<Shader>) -> Self {
+        Self {
+            geng: geng.clone(),
+            shader: shader.clone(),
+            quad: ugli::VertexBuffer::new_static(
+                geng.ugli(),
+                vec![
+                    Vertex {
+                        a_pos: vec2(-1.0, -1.0),
+                    },
+                    Vertex {
+                        a_pos: vec2(1.0, -1.0),
+                    },
+                    Vertex {
+                        a_pos: vec2(1.0, 1.0),
+                    },
+                    Vertex {
+                        a_pos: vec2(-1.0, 1.0),
+                    },
+                ],
+            ),
+            texture: None,
+            time: 0.0,
+        }
+    }
+    pub fn update(&mut self, delta_time: f32) {
+        self.time += delta_time;
+    }
+    pub fn take_texture(&mut self, size: Vec2<usize>) -> ugli::Texture {
+        match self.texture.take() {
+            Some(texture) if texture.size() == size => texture,
+            _ => ugli::Texture::new_uninitialized(self.geng.ugli(), size),
+        }
+    }
+    pub fn draw(
+        &mut self,
+        framebuffer: &mut ugli::Framebuffer,
+        texture: ugli::Texture,
+        settings: &Settings,
+        effects: Effects,
+    ) {
+        let enabled = |enabled: bool, value: f32| if enabled { value } else { 0.0 };
+        ugli::draw(
+            framebuffer,
+            &self.shader,
+            ugli::DrawMode::TriangleFan,
+            &self.quad,
+            ugli::uniforms! {
+                u_texture: &texture,
+                u_texture_size: texture.size(),
+                u_time: self.time,
+                u_vignette: enabled(settings.vignette, effects.vignette),
+                u_motion_blur: enabled(settings.motion_blur, effects.motion_blur),
+                u_underwater: enabled(settings.underwater, effects.underwater),
+                u_crt: enabled(settings.crt, 1.0),
+            },
+            ugli::DrawParameters::default(),
+        );
+        self.texture = Some(texture);
+    }
+}
@@ -0,0 +1,204 @@
+use super::*;
+
+pub trait Versioned: Serialize + for<'de> Deserialize<'de> + Default {
+    const VERSION: u32;
+    fn migrate(from: u32, data: &mut serde_json::Value) -> anyhow::Result<()>;
+}
+
+#[derive(Serialize, Deserialize)]
+struct Envelope {
+    version: u32,
+    data: serde_json::Value,
+}
+
+pub struct SaveData<T: Versioned> {
+    value: T,
+    path: String,
+    changed: Cell<bool>,
+}
+
+impl<T: Versioned> SaveData<T> {
+    pub fn load(path: &str) -> Self {
+        let value = match read(path) {
+            Some(contents) => match parse::<T>(&contents) {
+                Ok(value) => value,
+                Err(e) => {
+                    error!("Failed to load {:?}, falling back to defaults: {}", path, e);
+                    write(&format!("{}.bak", path), &contents);
+                    default()
+                }
+            },
+            None => default(),
+        };
+        let result = Self {
+            value,
+            path: path.to_owned(),
+            changed: Cell::new(false),
+        };
+        result.save();
+        result
+    }
+    pub fn save(&self) {
+        let envelope = Envelope {
+            version: T::VERSION,
+            data: serde_json::to_value(&self.value).expect("Failed to serialize"),
+        };
+        write(
+            &self.path,
+            &serde_json::to_string_pretty(&envelope).expect("Failed to serialize"),
+        );
+    }
+}
+
+fn parse<T: Versioned>(contents: &str) -> anyhow::Result<T> {
+    let json: serde_json::Value = serde_json::from_str(contents)?;
+    let Envelope { version, mut data } = match serde_json::from_value(json.clone()) {
+        Ok(envelope) => envelope,
+        Err(_) => Envelope {
+            version: json.get("version").and_then(|v| v.as_u64()).unwrap_or(0) as u32,
+            data: json,
+        },
+    };
+    if version > T::VERSION {
+        anyhow::bail!(
+            "saved with version {}, newer than supported {}",
+            version,
+            T::VERSION
+        );
+    }
+    for from in version..T::VERSION {
+        T::migrate(from, &mut data)?;
+    }
+    Ok(serde_json::from_value(data)?)
+}
+
+impl<T: Versioned> Deref for SaveData<T> {
+    type Target = T;
+    fn deref(&self) -> &T {
+        if self.changed.get() {
+            self.changed.set(false);
+            self.save();
+        }
+        &self.value
+    }
+}
+
+impl<T: Versioned> DerefMut for SaveData<T> {
+    fn deref_mut(&mut self) -> &mut T {
+        self.changed.set(true);
+        &mut self.value
+    }
+}
+
+impl<T: Versioned> Drop for SaveData<T> {
+    fn drop(&mut self) {
+        self.save();
+    }
+}
+
+fn read(path: &str) -> Option<String> {
+    #[cfg(target_arch = "wasm32")]
+    {
+        web_sys::window()?
+            .local_storage()
+            .ok()??
+            .get_item(path)
+            .ok()?
+    }
+    #[cfg(not(target_arch = "wasm32"))]
+    {
+        match std::fs::read_to_string(path) {
+            Ok(contents) => Some(contents),
+            Err(e) => {
+                warn!("Failed to open {:?}: {}", path, e);
+                None
+            }
+        }
+    }
+}
+
+fn write(path: &str, contents: &str) {
+    #[cfg(target_arch = "wasm32")]
+    {
+        if let Some(Ok(Some(storage))) = web_sys::window().map(|window| window.local_storage()) {
+            if storage.set_item(path, contents).is_err() {
+                error!("Failed to save {:?}", path);
+            }
+        }
+    }
+    #[cfg(not(target_arch = "wasm32"))]
+    {
+        if let Err(e) = std::fs::write(path, contents) {
+            error!("Failed to save {:?}: {}", path, e);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn volumes(settings: &Settings) -> (f64, f64, f64) {
+        (
+            settings.audio.music.volume,
+            settings.audio.sfx.volume,
+            settings.audio.voices.volume,
+        )
+    }
+
+    #[test]
+    fn v0_bare_volume() {
+        let settings = parse::<Settings>(r#"{"volume":0.3}"#).unwrap();
+        assert_eq!(volumes(&settings), (0.3, 0.3, 0.3));
+    }
+
+    #[test]
+    fn v1_flat_with_version() {
+        let settings =
+            parse::<Settings>(r#"{"version":1,"music_volume":0.2,"sfx_volume":0.7}"#).unwrap();
+        assert_eq!(volumes(&settings), (0.2, 0.7, 0.7));
+    }
+
+    #[test]
+    fn v2_envelope() {
+        let settings = parse::<Settings>(
+            r#"{"version":2,"data":{"music_volume":0.2,"sfx_volume":0.7,"reduced_motion":true}}"#,
+        )
+        .unwrap();
+        assert_eq!(volumes(&settings), (0.2, 0.7, 0.7));
+        assert!(settings.reduced_motion);
+    }
+
+    #[test]
+    fn v3_envelope() {
+        let settings = parse::<Settings>(
+            r#"{"version":3,"data":{"audio":{
+                "music":{"volume":0.1,"muted":true},
+                "sfx":{"volume":0.4},
+                "voices":{"volume":0.9}
+            }}}"#,
+        )
+        .unwrap();
+        assert_eq!(volumes(&settings), (0.1, 0.4, 0.9));
+        assert!(settings.audio.music.muted);
+        assert!(!settings.audio.sfx.muted);
+    }
+
+    #[test]
+    fn corrupt() {
+        assert!(parse::<Settings>(r#"{"version":3,"data":{"#).is_err());
+        assert!(parse::<Settings>(r#"{"version":2,"data":[1,2]}"#).is_err());
+    }
+
+    #[test]
+    fn newer_version() {
+        let newer = format!(r#"{{"version":{},"data":{{}}}}"#, Settings::VERSION + 1);
+        assert!(parse::<Settings>(&newer).is_err());
+    }
+
+    #[test]
+    fn unknown_migration() {
+        let mut data = serde_json::json!({});
+        assert!(Settings::migrate(Settings::VERSION, &mut data).is_err());
+    }
+}
@@ -0,0 +1,533 @@
+use super::*;
+
+fn next<T: Copy + PartialEq>(all: &[T], value: T) -> T {
+    let index = all.iter().position(|&item| item == value).unwrap_or(0);
+    all[(index + 1) % all.len()]
+}
+
+fn on_off(value: bool) -> &'static str {
+    if value {
+        translate("on")
+    } else {
+        translate("off")
+    }
+}
+
+fn option<'a>(
+    button: &'a mut geng::ui::Button,
+    label: &str,
+    value: &str,
+    theme: &Rc<geng::ui::Theme>,
+) -> impl geng::ui::Widget + 'a {
+    use geng::ui::*;
+    geng::ui::Button::text(button, format!("{}: {}", label, value), theme).padding_bottom(12.0)
+}
+
+fn slider<'a>(
+    slider: &'a mut geng::ui::Slider,
+    font: &'a Rc<geng::Font>,
+    label: &str,
+    value: f64,
+    range: RangeInclusive<f64>,
+    settings: &Rc<RefCell<save::SaveData<Settings>>>,
+    apply: fn(&mut Settings, f64),
+) -> impl geng::ui::Widget + 'a {
+    use geng::ui;
+    use geng::ui::*;
+    let settings = settings.clone();
+    ui::row![
+        geng::ui::Text::new(format!("{}: {:.2}", label, value), font, 24.0, Color::BLACK)
+            .padding_right(24.0),
+        slider
+            .ui(
+                value,
+                range,
+                Box::new(move |new_value| apply(&mut settings.borrow_mut(), new_value))
+            )
+            .fixed_size(vec2(100.0, 24.0)),
+    ]
+    .padding_bottom(12.0)
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+enum Page {
+    Audio,
+    Gameplay,
+    Graphics,
+    Controls,
+}
+
+impl Page {
+    const ALL: [Self; 4] = [Self::Audio, Self::Gameplay, Self::Graphics, Self::Controls];
+    fn name(self) -> &'static str {
+        match self {
+            Self::Audio => translate("audio"),
+            Self::Gameplay => translate("gameplay"),
+            Self::Graphics => translate("graphics"),
+            Self::Controls => translate("controls"),
+        }
+    }
+}
+
+struct BusControls {
+    bus: audio::Bus,
+    slider: geng::ui::Slider,
+    mute_button: geng::ui::Button,
+}
+
+impl BusControls {
+    fn new(theme: &Rc<geng::ui::Theme>, bus: audio::Bus) -> Self {
+        Self {
+            bus,
+            slider: geng::ui::Slider::new(theme),
+            mute_button: geng::ui::Button::new(),
+        }
+    }
+    fn ui<'a>(
+        &'a mut self,
+        font: &'a Rc<geng::Font>,
+        theme: &'a Rc<geng::ui::Theme>,
+        label: &str,
+        settings: &Rc<RefCell<save::SaveData<Settings>>>,
+    ) -> impl geng::ui::Widget + 'a {
+        use geng::ui;
+        use geng::ui::*;
+        let bus = self.bus;
+        let (volume, muted) = {
+            let settings = settings.borrow();
+            let bus = settings.audio.bus(bus);
+            (bus.volume, bus.muted)
+        };
+        let settings = settings.clone();
+        ui::row![
+            geng::ui::Text::new(label.to_owned(), font, 24.0, Color::BLACK).padding_right(24.0),
+            self.slider
+                .ui(
+                    volume,
+                    0.0..=1.0,
+                    Box::new(move |new_value| {
+                        settings.borrow_mut().audio.bus_mut(bus).volume = new_value;
+                    })
+                )
+                .fixed_size(vec2(100.0, 24.0))
+                .padding_right(24.0),
+            ui::Button::text(&mut self.mute_button, on_off(!muted), theme),
+        ]
+    }
+    fn update(&mut self, settings: &Rc<RefCell<save::SaveData<Settings>>>) {
+        if self.mute_button.clicked() {
+            let mut settings = settings.borrow_mut();
+            let bus = settings.audio.bus_mut(self.bus);
+            bus.muted = !bus.muted;
+        }
+    }
+}
+
+struct UiState {
+    font: Rc<geng::Font>,
+    theme: Rc<geng::ui::Theme>,
+    page: Page,
+    page_buttons: Vec<geng::ui::Button>,
+    music: BusControls,
+    sfx: BusControls,
+    voices: BusControls,
+    scheme_button: geng::ui::Button,
+    dead_zone_slider: geng::ui::Slider,
+    sensitivity_slider: geng::ui::Slider,
+    language_button: geng::ui::Button,
+    difficulty_button: geng::ui::Button,
+    time_of_day_button: geng::ui::Button,
+    weather_button: geng::ui::Button,
+    quality_button: geng::ui::Button,
+    reduced_motion_button: geng::ui::Button,
+    color_blind_button: geng::ui::Button,
+    post_processing_button: geng::ui::Button,
+    vignette_button: geng::ui::Button,
+    motion_blur_button: geng::ui::Button,
+    underwater_button: geng::ui::Button,
+    crt_button: geng::ui::Button,
+    skin_button: geng::ui::Button,
+    controls_button: geng::ui::Button,
+    tilt_button: geng::ui::Button,
+    back_button: geng::ui::Button,
+}
+
+impl UiState {
+    fn new(geng: &Rc<Geng>, font: Rc<geng::Font>) -> Self {
+        let ui_theme = Rc::new(geng::ui::Theme::default(geng));
+        Self {
+            font,
+            page: Page::Audio,
+            page_buttons: Page::ALL.iter().map(|_| geng::ui::Button::new()).collect(),
+            music: BusControls::new(&ui_theme, audio::Bus::Music),
+            sfx: BusControls::new(&ui_theme, audio::Bus::Sfx),
+            voices: BusControls::new(&ui_theme, audio::Bus::Voices),
+            scheme_button: geng::ui::Button::new(),
+            dead_zone_slider: geng::ui::Slider::new(&ui_theme),
+            sensitivity_slider: geng::ui::Slider::new(&ui_theme),
+            language_button: geng::ui::Button::new(),
+            difficulty_button: geng::ui::Button::new(),
+            time_of_day_button: geng::ui::Button::new(),
+            weather_button: geng::ui::Button::new(),
+            quality_button: geng::ui::Button::new(),
+            reduced_motion_button: geng::ui::Button::new(),
+            color_blind_button: geng::ui::Button::new(),
+            post_processing_button: geng::ui::Button::new(),
+            vignette_button: geng::ui::Button::new(),
+            motion_blur_button: geng::ui::Button::new(),
+            underwater_button: geng::ui::Button::new(),
+            crt_button: geng::ui::Button::new(),
+            skin_button: geng::ui::Button::new(),
+            controls_button: geng::ui::Button::new(),
+            tilt_button: geng::ui::Button::new(),
+            back_button: geng::ui::Button::new(),
+            theme: ui_theme,
+        }
+    }
+    fn ui<'a>(
+        &'a mut self,
+        settings: &Rc<RefCell<save::SaveData<Settings>>>,
+        skins: &skin::Skins,
+    ) -> impl geng::ui::Widget + 'a {
+        use geng::ui;
+        use geng::ui::*;
+        let current = settings.borrow();
+        let font = &self.font;
+        let theme = &self.theme;
+        let page = self.page;
+        let tabs = ui::row(
+            self.page_buttons
+                .iter_mut()
+                .zip(Page::ALL)
+                .map(|(button, item)| {
+                    let name = if item == page {
+                        format!("[{}]", item.name())
+                    } else {
+                        item.name().to_owned()
+                    };
+                    Box::new(ui::Button::text(button, name, theme).padding_right(24.0))
+                        as Box<dyn ui::Widget>
+                })
+                .collect(),
+        );
+        let page: Box<dyn ui::Widget + 'a> = match page {
+            Page::Audio => Box::new(ui::column![
+                self.music
+                    .ui(font, theme, translate("music"), settings)
+                    .padding_bottom(12.0),
+                self.sfx
+                    .ui(font, theme, translate("sound effects"), settings)
+                    .padding_bottom(12.0),
+                self.voices
+                    .ui(font, theme, translate("voices"), settings)
+                    .padding_bottom(12.0),
+            ]),
+            Page::Gameplay => Box::new(ui::column![
+                option(
+                    &mut self.language_button,
+                    translate("language"),
+                    current.language.name(),
+                    theme
+                ),
+                option(
+                    &mut self.difficulty_button,
+                    translate("difficulty"),
+                    current.difficulty.name(),
+                    theme
+                ),
+                option(
+                    &mut self.skin_button,
+                    translate("skin"),
+                    &skins.get(&current.skin).name,
+                    theme
+                ),
+                option(
+                    &mut self.time_of_day_button,
+                    translate("time of day"),
+                    current.time_of_day.name(),
+                    theme
+                ),
+                option(
+                    &mut self.weather_button,
+                    translate("weather"),
+                    current.weather.name(),
+                    theme
+                ),
+            ]),
+            Page::Graphics => Box::new(ui::column![
+                option(
+                    &mut self.quality_button,
+                    translate("graphics"),
+                    current.quality.name(),
+                    theme
+                ),
+                option(
+                    &mut self.reduced_motion_button,
+                    translate("reduced motion"),
+                    on_off(current.reduced_motion),
+                    theme
+                ),
+                option(
+                    &mut self.color_blind_button,
+                    translate("colour blind"),
+                    current.color_blind.name(),
+                    theme
+                ),
+                option(
+                    &mut self.post_processing_button,
+                    translate("post-processing"),
+                    on_off(current.post_processing.enabled),
+                    theme
+                ),
+                option(
+                    &mut self.vignette_button,
+                    translate("vignette"),
+                    on_off(current.post_processing.vignette),
+                    theme
+                ),
+                option(
+                    &mut self.motion_blur_button,
+                    translate("motion blur"),
+                    on_off(current.post_processing.motion_blur),
+                    theme
+                ),
+                option(
+                    &mut self.underwater_button,
+                    translate("underwater"),
+                    on_off(current.post_processing.underwater),
+                    theme
+                ),
+                option(
+                    &mut self.crt_button,
+                    translate("CRT filter"),
+                    on_off(current.post_processing.crt),
+                    theme
+                ),
+            ]),
+            Page::Controls => {
+                let mut column: Vec<Box<dyn ui::Widget + 'a>> = vec![
+                    Box::new(option(
+                        &mut self.scheme_button,
+                        translate("control scheme"),
+                        current.touch.scheme.name(),
+                        theme,
+                    )),
+                    Box::new(slider(
+                        &mut self.dead_zone_slider,
+                        font,
+                        translate("dead zone"),
+                        current.gamepad.dead_zone,
+                        0.0..=0.5,
+                        settings,
+                        |settings, value| settings.gamepad.dead_zone = value,
+                    )),
+                    Box::new(slider(
+                        &mut self.sensitivity_slider,
+                        font,
+                        translate("sensitivity"),
+                        current.gamepad.sensitivity,
+                        0.5..=2.0,
+                        settings,
+                        |settings, value| settings.gamepad.sensitivity = value,
+                    )),
+                ];
+                if tilt::supported() {
+                    column.push(Box::new(
+                        ui::Button::text(
+                            &mut self.tilt_button,
+                            if current.tilt.enabled {
+                                translate("tilt: on")
+                            } else {
+                                translate("tilt: off")
+                            },
+                            theme,
+                        )
+                        .padding_bottom(12.0),
+                    ));
+                }
+                column.push(Box::new(
+                    ui::Button::text(&mut self.controls_button, translate("controls"), theme)
+                        .padding_bottom(12.0),
+                ));
+                Box::new(ui::column(column))
+            }
+        };
+        ui::column![
+            tabs.padding_bottom(24.0),
+            page,
+            ui::Button::text(&mut self.back_button, translate("back"), theme),
+        ]
+        .align(vec2(0.5, 0.5))
+    }
+}
+
+pub struct SettingsScreen {
+    ctx: Context,
+    ui_state: UiState,
+    ui_controller: geng::ui::Controller,
+    rebind_screen: Option<controls::RebindScreen>,
+    transition: Option<geng::Transition>,
+}
+
+impl SettingsScreen {
+    pub fn new(ctx: &Context) -> Self {
+        Self {
+            ctx: ctx.clone(),
+            ui_state: UiState::new(&ctx.geng, ctx.font.clone()),
+            ui_controller: geng::ui::Controller::new(),
+            rebind_screen: None,
+            transition: None,
+        }
+    }
+}
+
+impl geng::State for SettingsScreen {
+    fn draw(&mut self, framebuffer: &mut ugli::Framebuffer) {
+        ugli::clear(framebuffer, Some(Color::rgb(0.8, 0.8, 1.0)), None);
+        let framebuffer_size = framebuffer.size().map(|x| x as f32);
+        let font_size = (framebuffer_size.y * 0.05).min(framebuffer_size.x * 0.03);
+        self.ctx.font.draw_aligned(
+            framebuffer,
+            translate("SETTINGS"),
+            vec2(framebuffer_size.x / 2.0, framebuffer_size.y * 0.8),
+            0.5,
+            font_size * 2.0,
+            Color::BLACK,
+        );
+        let settings = self.ctx.settings.clone();
+        self.ui_controller.draw(
+            &mut self.ui_state.ui(&settings, &self.ctx.assets().skins),
+            framebuffer,
+        );
+        if let Some(screen) = &mut self.rebind_screen {
+            screen.draw(
+                &self.ctx.geng,
+                &self.ctx.font,
+                framebuffer,
+                &settings.borrow().controls,
+            );
+        }
+    }
+    fn update(&mut self, delta_time: f64) {
+        let events = self.ctx.gamepads.borrow_mut().poll();
+        for event in events {
+            match event {
+                gamepad::Event::Restart | gamepad::Event::Pause => {
+                    if self.rebind_screen.take().is_none() {
+                        self.transition = Some(geng::Transition::Pop);
+                    }
+                }
+                gamepad::Event::Start => {}
+            }
+        }
+        let settings = self.ctx.settings.clone();
+        self.ui_controller.update(
+            &mut self.ui_state.ui(&settings, &self.ctx.assets().skins),
+            delta_time,
+        );
+        for (button, page) in self.ui_state.page_buttons.iter_mut().zip(Page::ALL) {
+            if button.clicked() {
+                self.ui_state.page = page;
+            }
+        }
+        self.ui_state.music.update(&settings);
+        self.ui_state.sfx.update(&settings);
+        self.ui_state.voices.update(&settings);
+        if self.ui_state.controls_button.clicked() {
+            self.rebind_screen = Some(default());
+        }
+        if self.ui_state.scheme_button.clicked() {
+            let mut settings = settings.borrow_mut();
+            settings.touch.scheme = next(&touch::Scheme::ALL, settings.touch.scheme);
+        }
+        if self.ui_state.language_button.clicked() {
+            let mut settings = settings.borrow_mut();
+            settings.language = next(&language::Language::ALL, settings.language);
+            settings.language.apply();
+        }
+        if self.ui_state.difficulty_button.clicked() {
+            let mut settings = settings.borrow_mut();
+            settings.difficulty = next(&world::Difficulty::ALL, settings.difficulty);
+        }
+        if self.ui_state.time_of_day_button.clicked() {
+            let mut settings = settings.borrow_mut();
+            settings.time_of_day = next(&lighting::TimeOfDaySetting::ALL, settings.time_of_day);
+        }
+        if self.ui_state.weather_button.clicked() {
+            let mut settings = settings.borrow_mut();
+            settings.weather = next(&lighting::Weather::ALL, settings.weather);
+        }
+        if self.ui_state.quality_button.clicked() {
+            let mut settings = settings.borrow_mut();
+            settings.quality = next(&world::Quality::ALL, settings.quality);
+        }
+        if self.ui_state.reduced_motion_button.clicked() {
+            let mut settings = settings.borrow_mut();
+            settings.reduced_motion = !settings.reduced_motion;
+        }
+        if self.ui_state.color_blind_button.clicked() {
+            let mut settings = settings.borrow_mut();
+            settings.color_blind = next(&lighting::ColorBlindMode::ALL, settings.color_blind);
+        }
+        if self.ui_state.post_processing_button.clicked() {
+            let mut settings = settings.borrow_mut();
+            settings.post_processing.enabled = !settings.post_processing.enabled;
+        }
+        if self.ui_state.vignette_button.clicked() {
+            let mut settings = settings.borrow_mut();
+            settings.post_processing.vignette = !settings.post_processing.vignette;
+        }
+        if self.ui_state.motion_blur_button.clicked() {
+            let mut settings = settings.borrow_mut();
+            settings.post_processing.motion_blur = !settings.post_processing.motion_blur;
+        }
+        if self.ui_state.underwater_button.clicked() {
+            let mut settings = settings.borrow_mut();
+            settings.post_processing.underwater = !settings.post_processing.underwater;
+        }
+        if self.ui_state.crt_button.clicked() {
+            let mut settings = settings.borrow_mut();
+            settings.post_processing.crt = !settings.post_processing.crt;
+        }
+        if self.ui_state.skin_button.clicked() {
+            let skins = &self.ctx.assets().skins;
+            let mut settings = settings.borrow_mut();
+            let current = skins.get(&settings.skin).name.as_str();
+            settings.skin = next(&skins.names(), current).to_owned();
+        }
+        if self.ui_state.back_button.clicked() {
+            self.transition = Some(geng::Transition::Pop);
+        }
+    }
+    fn handle_event(&mut self, event: geng::Event) {
+        let settings = self.ctx.settings.clone();
+        if let Some(screen) = &mut self.rebind_screen {
+            if !screen.handle_event(&mut settings.borrow_mut().controls, &event) {
+                self.rebind_screen = None;
+            }
+            return;
+        }
+        if self.ui_controller.handle_event(
+            &mut self.ui_state.ui(&settings, &self.ctx.assets().skins),
+            event.clone(),
+        ) {
+            if self.ui_state.tilt_button.clicked() {
+                let mut settings = settings.borrow_mut();
+                settings.tilt.enabled = !settings.tilt.enabled;
+                if settings.tilt.enabled {
+                    tilt::request_permission();
+                    self.ctx.tilt.calibrate();
+                }
+            }
+            return;
+        }
+        if let geng::Event::KeyDown { key } = event {
+            if self.ctx.bindings().matches(key, controls::Action::Back) {
+                self.transition = Some(geng::Transition::Pop);
+            }
+        }
+    }
+    fn transition(&mut self) -> Option<geng::Transition> {
+        self.transition.take()
+    }
+}
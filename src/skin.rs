@@ -0,0 +1,117 @@
+use super::*;
+
+pub struct Prop {
+    pub texture: ugli::Texture,
+    pub origin: Vec2<f32>,
+    pub width: f32,
+}
+
+pub struct Skin {
+    pub name: String,
+    pub houses: Vec<Rc<Prop>>,
+    pub beach_houses: Vec<Rc<Prop>>,
+    pub cars: Vec<Rc<Prop>>,
+    pub character: Rc<character::Assets>,
+}
+
+pub struct Skins {
+    skins: Vec<Rc<Skin>>,
+}
+
+impl Skins {
+    pub fn get(&self, name: &str) -> &Rc<Skin> {
+        self.skins
+            .iter()
+            .find(|skin| skin.name == name)
+            .unwrap_or(&self.skins[0])
+    }
+    pub fn names(&self) -> Vec<&str> {
+        self.skins.iter().map(|skin| skin.name.as_str()).collect()
+    }
+}
+
+async fn load_json<T: for<'de> Deserialize<'de>>(geng: &Rc<Geng>, path: &str) -> anyhow::Result<T> {
+    let json = <String as geng::LoadAsset>::load(geng, path).await?;
+    let value = serde_json::from_str(&json).map_err(|error| AssetError::Sidecar {
+        path: path.to_owned(),
+        error,
+    })?;
+    Ok(value)
+}
+
+async fn load_props(
+    geng: &Rc<Geng>,
+    manifest_path: &str,
+    configs: Vec<config::Prop>,
+) -> anyhow::Result<Vec<Rc<Prop>>> {
+    let mut props = Vec::new();
+    for config in configs {
+        let texture =
+            <ugli::Texture as geng::LoadAsset>::load(geng, &relative(manifest_path, &config.path))
+                .await?;
+        props.push(Rc::new(Prop {
+            texture,
+            origin: config.origin,
+            width: config.width,
+        }));
+    }
+    Ok(props)
+}
+
+async fn load_pose(
+    geng: &Rc<Geng>,
+    manifest_path: &str,
+    config: config::Pose,
+) -> anyhow::Result<character::Pose> {
+    Ok(character::Pose {
+        animation: <Animation as geng::LoadAsset>::load(
+            geng,
+            &relative(manifest_path, &config.path),
+        )
+        .await?,
+        origin: config.origin,
+        scale: config.scale,
+    })
+}
+
+async fn load_skin(geng: &Rc<Geng>, entry: config::SkinEntry) -> anyhow::Result<Skin> {
+    let path = &entry.manifest;
+    let manifest: config::Skin = load_json(geng, path).await?;
+    manifest.validate(path)?;
+    Ok(Skin {
+        name: entry.name,
+        houses: load_props(geng, path, manifest.houses).await?,
+        beach_houses: load_props(geng, path, manifest.beach_houses).await?,
+        cars: load_props(geng, path, manifest.cars).await?,
+        character: Rc::new(character::Assets {
+            run: load_pose(geng, path, manifest.character.run).await?,
+            fall: load_pose(geng, path, manifest.character.fall).await?,
+            fall_side: load_pose(geng, path, manifest.character.fall_side).await?,
+        }),
+    })
+}
+
+impl geng::LoadAsset for Skins {
+    fn load(geng: &Rc<Geng>, path: &str) -> geng::AssetFuture<Self> {
+        let geng = geng.clone();
+        let path = path.to_owned();
+        async move {
+            let entries: Vec<config::SkinEntry> = load_json(&geng, &path).await?;
+            if entries.is_empty() {
+                return Err(AssetError::Manifest {
+                    path,
+                    field: "skins",
+                }
+                .into());
+            }
+            let mut skins = Vec::new();
+            for mut entry in entries {
+                entry.manifest = relative(&path, &entry.manifest);
+                skins.push(Rc::new(load_skin(&geng, entry).await?));
+            }
+            Ok(Self { skins })
+        }
+        .boxed_local()
+    }
+    const DEFAULT_EXT: Option<&'static str> = Some("json");
+}
@@ -0,0 +1,97 @@
+use super::*;
+
+pub enum Sample {
+    Mono(geng::Sound),
+    Panned(geng::Sound, geng::Sound),
+}
+
+impl Sample {
+    pub fn effects(&self) -> Vec<geng::SoundEffect> {
+        match self {
+            Self::Mono(sound) => vec![sound.effect()],
+            Self::Panned(left, right) => vec![left.effect(), right.effect()],
+        }
+    }
+}
+
+pub struct Event {
+    pub bus: audio::Bus,
+    pub samples: Vec<Sample>,
+    pub volume: (f64, f64),
+    pub cooldown: f32,
+    pub max_voices: usize,
+    pub duration: f32,
+    pub range: f32,
+    pub duck: bool,
+}
+
+pub struct Table {
+    events: HashMap<String, Event>,
+}
+
+impl Table {
+    pub fn get(&self, name: &str) -> Option<&Event> {
+        self.events.get(name)
+    }
+}
+
+impl geng::LoadAsset for Table {
+    fn load(geng: &Rc<Geng>, path: &str) -> geng::AssetFuture<Self> {
+        let json = <String as geng::LoadAsset>::load(geng, path);
+        let dir = match path.rfind('/') {
+            Some(index) => path[..index].to_owned(),
+            None => ".".to_owned(),
+        };
+        let geng = geng.clone();
+        let path = path.to_owned();
+        async move {
+            let config: HashMap<String, config::Sound> = serde_json::from_str(&json.await?)
+                .map_err(|error| AssetError::Sidecar {
+                    path: path.clone(),
+                    error,
+                })?;
+            let mut events = HashMap::new();
+            for (name, config) in config {
+                config.validate(&format!("{}: {}", path, name))?;
+                let (panned, looped) = (config.panned, config.looped);
+                let samples = future::try_join_all(config.variants().iter().map(|sample| {
+                    let path = format!("{}/{}", dir, sample);
+                    let geng = geng.clone();
+                    async move {
+                        let load = |path: &str| {
+                            let sound = <geng::Sound as geng::LoadAsset>::load(&geng, path);
+                            async move {
+                                let mut sound = sound.await?;
+                                sound.looped = looped;
+                                Ok::<_, anyhow::Error>(sound)
+                            }
+                        };
+                        Ok::<_, anyhow::Error>(if panned {
+                            let [left, right] = wav::panned_paths(&path);
+                            Sample::Panned(load(&left).await?, load(&right).await?)
+                        } else {
+                            Sample::Mono(load(&path).await?)
+                        })
+                    }
+                }))
+                .await?;
+                events.insert(
+                    name,
+                    Event {
+                        bus: config.bus,
+                        samples,
+                        volume: config.volume,
+                        cooldown: config.cooldown,
+                        max_voices: config.max_voices,
+                        duration: config.duration,
+                        range: config.range,
+                        duck: config.duck,
+                    },
+                );
+            }
+            Ok(Self { events })
+        }
+        .boxed_local()
+    }
+    const DEFAULT_EXT: Option<&'static str> = Some("json");
+}
@@ -0,0 +1,108 @@
+use super::*;
+
+struct Paragraph {
+    text: String,
+    size: f32,
+    color: Color<f32>,
+}
+
+struct Line {
+    text: String,
+    size: f32,
+    color: Color<f32>,
+}
+
+#[derive(Default)]
+pub struct TextLayout {
+    paragraphs: Vec<Paragraph>,
+}
+
+impl TextLayout {
+    pub fn paragraph(mut self, text: impl Into<String>, size: f32, color: Color<f32>) -> Self {
+        self.paragraphs.push(Paragraph {
+            text: text.into(),
+            size,
+            color,
+        });
+        self
+    }
+    pub fn title(self, text: impl Into<String>) -> Self {
+        self.paragraph(text, 2.0, Color::BLACK)
+    }
+    pub fn text(self, text: impl Into<String>) -> Self {
+        self.paragraph(text, 1.0, Color::rgb(0.1, 0.1, 0.1))
+    }
+    pub fn small(self, text: impl Into<String>) -> Self {
+        self.paragraph(text, 0.8, Color::rgb(0.1, 0.1, 0.1))
+    }
+    pub fn space(self, size: f32) -> Self {
+        self.paragraph("", size, Color::TRANSPARENT_BLACK)
+    }
+    fn lines(&self, font: &geng::Font, font_size: f32, width: f32) -> Vec<Line> {
+        let mut lines = Vec::new();
+        for paragraph in &self.paragraphs {
+            let size = paragraph.size * font_size;
+            let mut current = String::new();
+            for word in paragraph.text.split_whitespace() {
+                let candidate = if current.is_empty() {
+                    word.to_owned()
+                } else {
+                    format!("{} {}", current, word)
+                };
+                if !current.is_empty() && font.measure(&candidate, size).width() > width {
+                    lines.push(Line {
+                        text: mem::replace(&mut current, word.to_owned()),
+                        size,
+                        color: paragraph.color,
+                    });
+                } else {
+                    current = candidate;
+                }
+            }
+            lines.push(Line {
+                text: current,
+                size,
+                color: paragraph.color,
+            });
+        }
+        lines
+    }
+    fn fit(&self, font: &geng::Font, framebuffer_size: Vec2<f32>, area: AABB<f32>) -> Vec<Line> {
+        let mut font_size = (framebuffer_size.y * 0.05).min(framebuffer_size.x * 0.03);
+        let mut lines = self.lines(font, font_size, area.width());
+        for _ in 0..3 {
+            let height: f32 = lines.iter().map(|line| line.size).sum();
+            if height <= area.height() {
+                break;
+            }
+            font_size *= area.height() / height;
+            lines = self.lines(font, font_size, area.width());
+        }
+        lines
+    }
+    pub fn ui<'a>(
+        &self,
+        font: &'a Rc<geng::Font>,
+        framebuffer_size: Vec2<f32>,
+        area: AABB<f32>,
+    ) -> impl geng::ui::Widget + 'a {
+        use geng::ui;
+        use geng::ui::*;
+        let lines = self
+            .fit(font, framebuffer_size, area)
+            .into_iter()
+            .map(|line| {
+                Box::new(
+                    ui::Text::new(line.text, font.clone(), line.size, line.color)
+                        .align(vec2(0.5, 0.5)),
+                ) as Box<dyn Widget + 'a>
+            })
+            .collect();
+        ui::column(lines).align(vec2(0.5, 1.0)).padding(
+            (framebuffer_size.y - area.y_max) as f64,
+            (framebuffer_size.x - area.x_max) as f64,
+            area.y_min as f64,
+            area.x_min as f64,
+        )
+    }
+}
@@ -0,0 +1,145 @@
+use super::*;
+
+#[derive(Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Settings {
+    pub enabled: bool,
+    pub max_angle: f32,
+}
+
+impl Default for Settings {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            max_angle: 30.0,
+        }
+    }
+}
+
+pub fn supported() -> bool {
+    cfg!(target_arch = "wasm32")
+}
+
+pub fn request_permission() {
+    #[cfg(target_arch = "wasm32")]
+    {
+        let global = js_sys::global();
+        let constructor = match js_sys::Reflect::get(&global, &"DeviceOrientationEvent".into()) {
+            Ok(constructor) => constructor,
+            Err(_) => return,
+        };
+        let request = js_sys::Reflect::get(&constructor, &"requestPermission".into())
+            .ok()
+            .and_then(|request| request.dyn_into::<js_sys::Function>().ok());
+        if let Some(request) = request {
+            if let Err(e) = request.call0(&constructor) {
+                warn!("Failed to request device orientation permission: {:?}", e);
+            }
+        }
+    }
+}
+
+#[cfg(target_arch = "wasm32")]
+fn screen_angle() -> f64 {
+    let global = js_sys::global();
+    js_sys::Reflect::get(&global, &"screen".into())
+        .and_then(|screen| js_sys::Reflect::get(&screen, &"orientation".into()))
+        .and_then(|orientation| js_sys::Reflect::get(&orientation, &"angle".into()))
+        .ok()
+        .and_then(|angle| angle.as_f64())
+        .or_else(|| {
+            js_sys::Reflect::get(&global, &"orientation".into())
+                .ok()
+                .and_then(|angle| angle.as_f64())
+        })
+        .unwrap_or(0.0)
+}
+
+#[cfg(target_arch = "wasm32")]
+fn steering_axis(screen_angle: f64, beta: f64, gamma: f64) -> f64 {
+    match screen_angle.rem_euclid(360.0).round() as i32 {
+        90 => beta,
+        180 => -gamma,
+        270 => -beta,
+        _ => gamma,
+    }
+}
+
+pub struct Tilt {
+    angle: Rc<Cell<Option<f32>>>,
+    #[cfg(target_arch = "wasm32")]
+    listener: Closure<dyn FnMut(web_sys::Event)>,
+    calibration: Cell<Option<f32>>,
+}
+
+impl Default for Tilt {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Tilt {
+    pub fn new() -> Self {
+        let angle = Rc::new(Cell::new(None));
+        #[cfg(target_arch = "wasm32")]
+        let listener = {
+            let angle = angle.clone();
+            let listener = Closure::wrap(Box::new(move |event: web_sys::Event| {
+                let get = |name: &str| {
+                    js_sys::Reflect::get(&event, &name.into())
+                        .ok()
+                        .and_then(|value| value.as_f64())
+                };
+                angle.set(match (get("beta"), get("gamma")) {
+                    (Some(beta), Some(gamma)) => {
+                        Some(steering_axis(screen_angle(), beta, gamma) as f32)
+                    }
+                    _ => None,
+                });
+            }) as Box<dyn FnMut(web_sys::Event)>);
+            if let Err(e) = web_sys::window().unwrap().add_event_listener_with_callback(
+                "deviceorientation",
+                listener.as_ref().unchecked_ref(),
+            ) {
+                warn!("Failed to listen to device orientation: {:?}", e);
+            }
+            listener
+        };
+        Self {
+            angle,
+            #[cfg(target_arch = "wasm32")]
+            listener,
+            calibration: Cell::new(None),
+        }
+    }
+    pub fn calibrate(&self) {
+        self.calibration.set(self.angle.get());
+    }
+    pub fn target(&self, settings: &Settings) -> Option<f32> {
+        if !settings.enabled {
+            return None;
+        }
+        let angle = self.angle.get()?;
+        let calibration = match self.calibration.get() {
+            Some(calibration) => calibration,
+            None => {
+                self.calibration.set(Some(angle));
+                angle
+            }
+        };
+        Some(clamp_abs((angle - calibration) / settings.max_angle, 1.0))
+    }
+}
+
+#[cfg(target_arch = "wasm32")]
+impl Drop for Tilt {
+    fn drop(&mut self) {
+        web_sys::window()
+            .unwrap()
+            .remove_event_listener_with_callback(
+                "deviceorientation",
+                self.listener.as_ref().unchecked_ref(),
+            )
+            .ok();
+    }
+}
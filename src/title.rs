@@ -0,0 +1,121 @@
+use super::*;
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+enum Item {
+    Play,
+    Settings,
+    Credits,
+}
+
+impl Item {
+    const ALL: [Self; 3] = [Self::Play, Self::Settings, Self::Credits];
+    fn name(self) -> &'static str {
+        match self {
+            Self::Play => translate("play"),
+            Self::Settings => translate("settings"),
+            Self::Credits => translate("credits"),
+        }
+    }
+}
+
+pub struct TitleScreen {
+    ctx: Context,
+    world: Option<World>,
+    menu: menu::Menu,
+    ui_controller: geng::ui::Controller,
+    transition: Option<geng::Transition>,
+}
+
+impl TitleScreen {
+    pub fn new(ctx: &Context) -> Self {
+        Self {
+            ctx: ctx.clone(),
+            world: Some(World::new(ctx)),
+            menu: default(),
+            ui_controller: geng::ui::Controller::new(),
+            transition: None,
+        }
+    }
+    fn select(&mut self, item: Item) {
+        self.transition = Some(match item {
+            Item::Play => match self.world.take() {
+                Some(world) => geng::Transition::Switch(Box::new(Playing::new(&self.ctx, world))),
+                None => return,
+            },
+            Item::Settings => {
+                geng::Transition::Push(Box::new(settings_screen::SettingsScreen::new(&self.ctx)))
+            }
+            Item::Credits => geng::Transition::Push(Box::new(credits::Credits::new(&self.ctx))),
+        });
+    }
+}
+
+impl geng::State for TitleScreen {
+    fn draw(&mut self, framebuffer: &mut ugli::Framebuffer) {
+        if let Some(world) = &mut self.world {
+            world.draw(framebuffer);
+        }
+        let framebuffer_size = framebuffer.size().map(|x| x as f32);
+        let font = &self.ctx.font;
+        self.ctx.geng.draw_2d().quad(
+            framebuffer,
+            AABB::pos_size(vec2(0.0, 0.0), framebuffer_size),
+            Color::rgba(0.8, 0.8, 1.0, 0.5),
+        );
+        let layout = TextLayout::default()
+            .title(translate("TSUNAMI ESCAPE"))
+            .space(1.0)
+            .text(translate("A tsunami is coming"))
+            .text(translate("The city will soon be under water"))
+            .text(translate("Can you save it? No!"))
+            .text(translate("Can you save yourself? No!"))
+            .text(translate("How long can you last? Yes!"));
+        self.ui_controller.draw(
+            &mut layout.ui(
+                font,
+                framebuffer_size,
+                AABB::from_corners(
+                    vec2(framebuffer_size.x * 0.05, framebuffer_size.y * 0.4),
+                    framebuffer_size * 0.95,
+                ),
+            ),
+            framebuffer,
+        );
+        let font_size = (framebuffer_size.y * 0.05).min(framebuffer_size.x * 0.03);
+        let items: Vec<&str> = Item::ALL.iter().map(|item| item.name()).collect();
+        self.menu.draw(
+            font,
+            framebuffer,
+            &items,
+            framebuffer_size.y * 0.35,
+            font_size,
+        );
+    }
+    fn update(&mut self, delta_time: f64) {
+        let events = self.ctx.gamepads.borrow_mut().poll();
+        for event in events {
+            match event {
+                gamepad::Event::Start | gamepad::Event::Pause => self.select(Item::Play),
+                gamepad::Event::Restart => {}
+            }
+        }
+        if let Some(world) = &mut self.world {
+            world.update(delta_time as f32, 0.0);
+        }
+    }
+    fn handle_event(&mut self, event: geng::Event) {
+        let index = self
+            .menu
+            .handle_event(&self.ctx.bindings(), &event, Item::ALL.len());
+        if let Some(index) = index {
+            self.select(Item::ALL[index]);
+        } else if let geng::Event::KeyDown { key } = event {
+            if self.ctx.bindings().matches(key, controls::Action::Start) {
+                self.select(Item::Play);
+            }
+        }
+    }
+    fn transition(&mut self) -> Option<geng::Transition> {
+        self.transition.take()
+    }
+}
@@ -0,0 +1,240 @@
+use super::*;
+
+const SWIPE_TIME: f32 = 0.5;
+const LANE_WIDTH: f32 = 0.5;
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Scheme {
+    Absolute,
+    Relative,
+    Swipe,
+}
+
+#[derive(Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Settings {
+    pub scheme: Scheme,
+    pub drag_sensitivity: f32,
+    pub swipe_distance: f32,
+}
+
+impl Scheme {
+    pub const ALL: [Self; 3] = [Self::Absolute, Self::Relative, Self::Swipe];
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Absolute => translate("absolute"),
+            Self::Relative => translate("relative"),
+            Self::Swipe => translate("swipe"),
+        }
+    }
+}
+
+impl Default for Settings {
+    fn default() -> Self {
+        Self {
+            scheme: Scheme::Relative,
+            drag_sensitivity: 1.5,
+            swipe_distance: 0.1,
+        }
+    }
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Gesture {
+    Tap,
+    SwipeUp,
+    SwipeDown,
+    SwipeLeft,
+    SwipeRight,
+    TwoFingerTap,
+}
+
+struct Drag {
+    start: Vec2<f32>,
+    position: Vec2<f32>,
+    anchor: f32,
+    time: f32,
+}
+
+#[derive(Default)]
+pub struct Touch {
+    drag: Option<Drag>,
+    target: Option<f32>,
+}
+
+impl Touch {
+    pub fn update(&mut self, delta_time: f32) {
+        if let Some(drag) = &mut self.drag {
+            drag.time += delta_time;
+        }
+    }
+    pub fn target(&self) -> Option<f32> {
+        self.target
+    }
+    pub fn reset(&mut self) {
+        self.drag = None;
+        self.target = None;
+    }
+    pub fn handle_event(
+        &mut self,
+        settings: &Settings,
+        event: &geng::Event,
+        window_size: Vec2<f32>,
+        current: f32,
+    ) -> Option<Gesture> {
+        let scale = window_size.x.min(window_size.y) / 2.0;
+        match event {
+            geng::Event::TouchStart { touches } if touches.len() >= 2 => {
+                self.reset();
+                return Some(Gesture::TwoFingerTap);
+            }
+            geng::Event::TouchStart { touches } => {
+                let position = touches[0].position.map(|x| x as f32);
+                self.drag = Some(Drag {
+                    start: position,
+                    position,
+                    anchor: current,
+                    time: 0.0,
+                });
+                match settings.scheme {
+                    Scheme::Absolute => {
+                        self.target = Some((position.x - window_size.x / 2.0) / scale)
+                    }
+                    Scheme::Relative => self.target = Some(current),
+                    Scheme::Swipe => {}
+                }
+            }
+            geng::Event::TouchMove { touches } => {
+                let drag = self.drag.as_mut()?;
+                drag.position = touches[0].position.map(|x| x as f32);
+                match settings.scheme {
+                    Scheme::Absolute => {
+                        self.target = Some((drag.position.x - window_size.x / 2.0) / scale)
+                    }
+                    Scheme::Relative => {
+                        self.target = Some(clamp_abs(
+                            drag.anchor
+                                + (drag.position.x - drag.start.x) / scale
+                                    * settings.drag_sensitivity,
+                            1.0,
+                        ))
+                    }
+                    Scheme::Swipe => {}
+                }
+            }
+            geng::Event::TouchEnd => {
+                let drag = self.drag.take()?;
+                if settings.scheme != Scheme::Swipe {
+                    self.target = None;
+                }
+                let delta = drag.position - drag.start;
+                if drag.time > SWIPE_TIME {
+                    return None;
+                }
+                if delta.len() < settings.swipe_distance * scale * 2.0 {
+                    return Some(Gesture::Tap);
+                }
+                let gesture = if delta.x.abs() > delta.y.abs() {
+                    if delta.x > 0.0 {
+                        Gesture::SwipeRight
+                    } else {
+                        Gesture::SwipeLeft
+                    }
+                } else if delta.y > 0.0 {
+                    Gesture::SwipeUp
+                } else {
+                    Gesture::SwipeDown
+                };
+                if settings.scheme == Scheme::Swipe {
+                    let lane = self.target.unwrap_or(drag.anchor);
+                    match gesture {
+                        Gesture::SwipeLeft => self.target = Some((lane - LANE_WIDTH).max(-1.0)),
+                        Gesture::SwipeRight => self.target = Some((lane + LANE_WIDTH).min(1.0)),
+                        _ => {}
+                    }
+                }
+                return Some(gesture);
+            }
+            _ => {}
+        }
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const WINDOW_SIZE: Vec2<f32> = Vec2 { x: 400.0, y: 800.0 };
+
+    fn touch(event: fn(Vec<geng::TouchPoint>) -> geng::Event, x: f64, y: f64) -> geng::Event {
+        event(vec![geng::TouchPoint {
+            position: vec2(x, y),
+        }])
+    }
+
+    fn gesture(to: Vec2<f64>, time: f32) -> Option<Gesture> {
+        let settings = Settings::default();
+        let mut recognizer = Touch::default();
+        let mut send =
+            |event: geng::Event| recognizer.handle_event(&settings, &event, WINDOW_SIZE, 0.0);
+        send(touch(
+            |touches| geng::Event::TouchStart { touches },
+            200.0,
+            400.0,
+        ));
+        send(touch(
+            |touches| geng::Event::TouchMove { touches },
+            200.0 + to.x,
+            400.0 + to.y,
+        ));
+        recognizer.update(time);
+        recognizer.handle_event(&settings, &geng::Event::TouchEnd, WINDOW_SIZE, 0.0)
+    }
+
+    #[test]
+    fn tap() {
+        assert_eq!(gesture(vec2(0.0, 0.0), 0.1), Some(Gesture::Tap));
+        assert_eq!(gesture(vec2(5.0, -5.0), 0.1), Some(Gesture::Tap));
+    }
+
+    #[test]
+    fn swipe() {
+        assert_eq!(gesture(vec2(100.0, 0.0), 0.1), Some(Gesture::SwipeRight));
+        assert_eq!(gesture(vec2(-100.0, 10.0), 0.1), Some(Gesture::SwipeLeft));
+        assert_eq!(gesture(vec2(0.0, 100.0), 0.1), Some(Gesture::SwipeUp));
+        assert_eq!(gesture(vec2(10.0, -100.0), 0.1), Some(Gesture::SwipeDown));
+    }
+
+    #[test]
+    fn swipe_distance_threshold() {
+        // swipe_distance 0.1 of the half-size 200 scale, doubled: 40 pixels
+        assert_eq!(gesture(vec2(39.0, 0.0), 0.1), Some(Gesture::Tap));
+        assert_eq!(gesture(vec2(41.0, 0.0), 0.1), Some(Gesture::SwipeRight));
+    }
+
+    #[test]
+    fn hold() {
+        assert_eq!(gesture(vec2(0.0, 0.0), SWIPE_TIME + 0.1), None);
+        assert_eq!(gesture(vec2(0.0, -100.0), SWIPE_TIME + 0.1), None);
+    }
+
+    #[test]
+    fn two_finger_tap() {
+        let mut recognizer = Touch::default();
+        let event = geng::Event::TouchStart {
+            touches: vec![
+                geng::TouchPoint {
+                    position: vec2(100.0, 100.0),
+                },
+                geng::TouchPoint {
+                    position: vec2(300.0, 100.0),
+                },
+            ],
+        };
+        assert_eq!(
+            recognizer.handle_event(&Settings::default(), &event, WINDOW_SIZE, 0.0),
+            Some(Gesture::TwoFingerTap)
+        );
+    }
+}
@@ -0,0 +1,108 @@
+use super::*;
+
+thread_local! {
+    static INTERNED: RefCell<HashSet<&'static str>> = RefCell::new(HashSet::new());
+}
+
+fn intern(text: &str) -> &'static str {
+    INTERNED.with(|interned| {
+        let mut interned = interned.borrow_mut();
+        if let Some(&text) = interned.get(text) {
+            return text;
+        }
+        let text: &'static str = Box::leak(text.to_owned().into_boxed_str());
+        interned.insert(text);
+        text
+    })
+}
+
+pub struct Translations {
+    groups: Vec<HashMap<String, String>>,
+}
+
+impl Translations {
+    pub fn parse(path: &str, source: &str) -> Result<Self, AssetError> {
+        let error = |line: usize, reason: &'static str| AssetError::Translation {
+            path: path.to_owned(),
+            line: line + 1,
+            reason,
+        };
+        let mut groups = Vec::new();
+        let mut group = HashMap::new();
+        let mut start = 0;
+        for (index, line) in source.lines().chain(std::iter::once("")).enumerate() {
+            if line.trim().is_empty() {
+                if !group.is_empty() {
+                    if !group.contains_key("en") {
+                        return Err(error(start, "group has no en"));
+                    }
+                    groups.push(mem::take(&mut group));
+                }
+                continue;
+            }
+            if group.is_empty() {
+                start = index;
+            }
+            let (locale, text) = line
+                .split_once('=')
+                .ok_or_else(|| error(index, "expected locale=text"))?;
+            if group.insert(locale.to_owned(), text.to_owned()).is_some() {
+                return Err(error(index, "duplicate locale in group"));
+            }
+        }
+        Ok(Self { groups })
+    }
+    pub fn install(&self) {
+        for group in &self.groups {
+            let source = intern(&group["en"]);
+            for (locale, text) in group {
+                if locale != "en" {
+                    add_translation(locale, source, intern(text));
+                }
+            }
+        }
+    }
+}
+
+impl geng::LoadAsset for Translations {
+    fn load(geng: &Rc<Geng>, path: &str) -> geng::AssetFuture<Self> {
+        let source = <String as geng::LoadAsset>::load(geng, path);
+        let path = path.to_owned();
+        async move { Ok(Self::parse(&path, &source.await?)?) }.boxed_local()
+    }
+    const DEFAULT_EXT: Option<&'static str> = Some("txt");
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse() {
+        let translations = Translations::parse("test.txt", "en=a\nru=б\n\nen=c\n").unwrap();
+        assert_eq!(translations.groups.len(), 2);
+        assert_eq!(translations.groups[0]["ru"], "б");
+    }
+
+    #[test]
+    fn malformed() {
+        for (source, line) in [
+            ("en=a\nru\n", 2),
+            ("en=a\n\nru=b\n", 3),
+            ("en=a\nen=b\n", 2),
+        ] {
+            match Translations::parse("test.txt", source) {
+                Err(AssetError::Translation {
+                    line: error_line, ..
+                }) => assert_eq!(error_line, line),
+                _ => panic!("{:?} should not parse", source),
+            }
+        }
+    }
+
+    #[test]
+    fn interned_once() {
+        let source = String::from("reload");
+        assert!(std::ptr::eq(intern("reload"), intern(&source)));
+    }
+}
@@ -0,0 +1,81 @@
+use super::*;
+
+#[derive(geng::Assets)]
+pub struct Assets {
+    shader: Shader,
+    pub config: config::Water,
+}
+
+#[derive(ugli::Vertex, Copy, Clone)]
+struct Vertex {
+    a_pos: Vec2<f32>,
+    a_vt: Vec2<f32>,
+}
+
+pub struct Water {
+    geng: Rc<Geng>,
+    assets: Rc<Assets>,
+    time: f32,
+}
+
+impl Water {
+    pub fn new(geng: &Rc<Geng>, assets: &Rc<Assets>) -> Self {
+        Self {
+            geng: geng.clone(),
+            assets: assets.clone(),
+            time: 0.0,
+        }
+    }
+    pub fn enabled(&self) -> bool {
+        self.assets.config.enabled
+    }
+    pub fn update(&mut self, delta_time: f32) {
+        self.time += delta_time;
+    }
+    pub fn draw(
+        &self,
+        framebuffer: &mut ugli::Framebuffer,
+        center_x: f32,
+        y: f32,
+        height: f32,
+        color: Color<f32>,
+    ) {
+        let framebuffer_size = framebuffer.size();
+        let vertex = |x: f32, v: f32| Vertex {
+            a_pos: vec2(x, y + height * v),
+            a_vt: vec2((x - center_x) / height, v),
+        };
+        let config = &self.assets.config;
+        ugli::draw(
+            framebuffer,
+            &self.assets.shader,
+            ugli::DrawMode::TriangleFan,
+            &ugli::VertexBuffer::new_dynamic(
+                self.geng.ugli(),
+                vec![
+                    vertex(0.0, 0.0),
+                    vertex(framebuffer_size.x as f32, 0.0),
+                    vertex(framebuffer_size.x as f32, 1.0),
+                    vertex(0.0, 1.0),
+                ],
+            ),
+            ugli::uniforms! {
+                u_framebuffer_size: framebuffer_size,
+                u_time: self.time,
+                u_color: color,
+                u_deep_color: config.deep_color,
+                u_shallow_color: config.shallow_color,
+                u_foam_color: config.foam_color,
+                u_crest_height: config.crest_height,
+                u_amplitude: config.amplitude,
+                u_frequency: config.frequency,
+                u_speed: config.speed,
+                u_foam_width: config.foam_width,
+            },
+            ugli::DrawParameters {
+                blend_mode: Some(default()),
+                ..default()
+            },
+        );
+    }
+}
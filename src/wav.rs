@@ -0,0 +1,217 @@
+use super::*;
+
+pub const PITCHES: [u32; 3] = [94, 100, 106];
+
+pub fn pitched_path(path: &str, pitch: u32) -> String {
+    if pitch == 100 {
+        return path.to_owned();
+    }
+    let (stem, ext) = path.rsplit_once('.').unwrap_or((path, "wav"));
+    format!("{}_p{}.{}", stem, pitch, ext)
+}
+
+pub fn panned_paths(path: &str) -> [String; 2] {
+    let (stem, ext) = path.rsplit_once('.').unwrap_or((path, "wav"));
+    [
+        format!("{}_left.{}", stem, ext),
+        format!("{}_right.{}", stem, ext),
+    ]
+}
+
+pub struct Wav {
+    pub sample_rate: u32,
+    pub samples: Vec<i16>,
+}
+
+fn wav_error(path: &str, reason: &'static str) -> AssetError {
+    AssetError::Wav {
+        path: path.to_owned(),
+        reason,
+    }
+}
+
+fn u16_at(data: &[u8], offset: usize) -> u16 {
+    u16::from_le_bytes([data[offset], data[offset + 1]])
+}
+
+fn u32_at(data: &[u8], offset: usize) -> u32 {
+    u32::from_le_bytes([
+        data[offset],
+        data[offset + 1],
+        data[offset + 2],
+        data[offset + 3],
+    ])
+}
+
+pub fn decode(path: &str, data: &[u8]) -> Result<Wav, AssetError> {
+    if data.len() < 12 || &data[0..4] != b"RIFF" || &data[8..12] != b"WAVE" {
+        return Err(wav_error(path, "not a RIFF WAVE file"));
+    }
+    let mut format = None;
+    let mut offset = 12;
+    while offset + 8 <= data.len() {
+        let id = &data[offset..offset + 4];
+        let size = u32_at(data, offset + 4) as usize;
+        let body = offset + 8;
+        let end = body
+            .checked_add(size)
+            .filter(|&end| end <= data.len())
+            .ok_or_else(|| wav_error(path, "truncated chunk"))?;
+        match id {
+            b"fmt " => {
+                if size < 16 {
+                    return Err(wav_error(path, "truncated format chunk"));
+                }
+                if u16_at(data, body) != 1 || u16_at(data, body + 14) != 16 {
+                    return Err(wav_error(path, "expected 16-bit PCM"));
+                }
+                format = Some((u16_at(data, body + 2), u32_at(data, body + 4)));
+            }
+            b"data" => {
+                let (channels, sample_rate) =
+                    format.ok_or_else(|| wav_error(path, "data before format chunk"))?;
+                if channels == 0 {
+                    return Err(wav_error(path, "no channels"));
+                }
+                let samples = data[body..end]
+                    .chunks_exact(2 * channels as usize)
+                    .map(|frame| {
+                        let sum: i32 = frame
+                            .chunks_exact(2)
+                            .map(|sample| i16::from_le_bytes([sample[0], sample[1]]) as i32)
+                            .sum();
+                        (sum / channels as i32) as i16
+                    })
+                    .collect();
+                return Ok(Wav {
+                    sample_rate,
+                    samples,
+                });
+            }
+            _ => {}
+        }
+        offset = end + size % 2;
+    }
+    Err(wav_error(path, "no data chunk"))
+}
+
+pub fn encode_stereo(sample_rate: u32, frames: impl Iterator<Item = (i16, i16)>) -> Vec<u8> {
+    let mut data = Vec::new();
+    for (left, right) in frames {
+        data.extend_from_slice(&left.to_le_bytes());
+        data.extend_from_slice(&right.to_le_bytes());
+    }
+    let mut wav = Vec::with_capacity(44 + data.len());
+    wav.extend_from_slice(b"RIFF");
+    wav.extend_from_slice(&(36 + data.len() as u32).to_le_bytes());
+    wav.extend_from_slice(b"WAVEfmt ");
+    wav.extend_from_slice(&16u32.to_le_bytes());
+    wav.extend_from_slice(&1u16.to_le_bytes());
+    wav.extend_from_slice(&2u16.to_le_bytes());
+    wav.extend_from_slice(&sample_rate.to_le_bytes());
+    wav.extend_from_slice(&(sample_rate * 4).to_le_bytes());
+    wav.extend_from_slice(&4u16.to_le_bytes());
+    wav.extend_from_slice(&16u16.to_le_bytes());
+    wav.extend_from_slice(b"data");
+    wav.extend_from_slice(&(data.len() as u32).to_le_bytes());
+    wav.extend_from_slice(&data);
+    wav
+}
+
+pub fn resample(wav: &Wav, pitch: u32) -> Wav {
+    let step = pitch as f64 / 100.0;
+    let last = wav.samples.len().saturating_sub(1);
+    let len = if wav.samples.is_empty() {
+        0
+    } else {
+        (last as f64 / step) as usize + 1
+    };
+    let samples = (0..len)
+        .map(|index| {
+            let position = index as f64 * step;
+            let before = position.floor() as usize;
+            let after = (before + 1).min(last);
+            let t = position - before as f64;
+            let sample = wav.samples[before] as f64 * (1.0 - t) + wav.samples[after] as f64 * t;
+            sample.round() as i16
+        })
+        .collect();
+    Wav {
+        sample_rate: wav.sample_rate,
+        samples,
+    }
+}
+
+pub fn pan(wav: &Wav) -> [Vec<u8>; 2] {
+    let samples = || wav.samples.iter().copied();
+    [
+        encode_stereo(wav.sample_rate, samples().map(|sample| (sample, 0))),
+        encode_stereo(wav.sample_rate, samples().map(|sample| (0, sample))),
+    ]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn pan_splits_channels() {
+        let mono = [1000, -2000, 3000];
+        let source = encode_stereo(22050, mono.iter().map(|&sample| (sample, sample)));
+        let [left, right] = pan(&decode("test.wav", &source).unwrap());
+        let frames = |data: &[u8]| -> Vec<i16> {
+            data[44..]
+                .chunks_exact(2)
+                .map(|sample| i16::from_le_bytes([sample[0], sample[1]]))
+                .collect()
+        };
+        assert_eq!(frames(&left), vec![1000, 0, -2000, 0, 3000, 0]);
+        assert_eq!(frames(&right), vec![0, 1000, 0, -2000, 0, 3000]);
+        let decoded = decode("left.wav", &left).unwrap();
+        assert_eq!(decoded.sample_rate, 22050);
+        assert_eq!(decoded.samples, vec![500, -1000, 1500]);
+    }
+
+    #[test]
+    fn rejects_non_pcm() {
+        let mut data = encode_stereo(22050, std::iter::empty());
+        data[20] = 3;
+        assert!(matches!(
+            decode("float.wav", &data),
+            Err(AssetError::Wav { .. })
+        ));
+    }
+
+    #[test]
+    fn truncated() {
+        let data = encode_stereo(22050, std::iter::once((1, 1)));
+        assert!(matches!(
+            decode("short.wav", &data[..data.len() - 10]),
+            Err(AssetError::Wav { .. })
+        ));
+    }
+
+    #[test]
+    fn resample_pitch() {
+        let wav = Wav {
+            sample_rate: 22050,
+            samples: vec![0, 100, 200, 300, 400],
+        };
+        assert_eq!(resample(&wav, 100).samples, wav.samples);
+        assert_eq!(resample(&wav, 200).samples, vec![0, 200, 400]);
+        assert_eq!(
+            resample(&wav, 50).samples,
+            vec![0, 50, 100, 150, 200, 250, 300, 350, 400]
+        );
+    }
+
+    #[test]
+    fn paths() {
+        assert_eq!(
+            panned_paths("sounds/cry.wav"),
+            ["sounds/cry_left.wav", "sounds/cry_right.wav"]
+        );
+        assert_eq!(pitched_path("sounds/cry.wav", 100), "sounds/cry.wav");
+        assert_eq!(pitched_path("sounds/cry.wav", 94), "sounds/cry_p94.wav");
+    }
+}
@@ -0,0 +1,827 @@
+use super::*;
+
+const BEACH_START: f32 = 2.0;
+const BEACH_END: f32 = 20.0;
+const NEAR_MISS_RATIO: f32 = 1.5;
+const HONK_DISTANCE: f32 = 3.0;
+const HONK_CHANCE: f64 = 0.3;
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Serialize, Deserialize, Default)]
+pub enum Difficulty {
+    Easy,
+    #[default]
+    Normal,
+    Hard,
+}
+
+impl Difficulty {
+    pub const ALL: [Self; 3] = [Self::Easy, Self::Normal, Self::Hard];
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Easy => translate("easy"),
+            Self::Normal => translate("normal"),
+            Self::Hard => translate("hard"),
+        }
+    }
+    fn speed_growth(self) -> f32 {
+        match self {
+            Self::Easy => 0.03,
+            Self::Normal => 0.05,
+            Self::Hard => 0.08,
+        }
+    }
+    fn obstacle_spacing(self) -> f32 {
+        match self {
+            Self::Easy => 2.5,
+            Self::Normal => 2.0,
+            Self::Hard => 1.5,
+        }
+    }
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Serialize, Deserialize, Default)]
+pub enum Quality {
+    Low,
+    Medium,
+    #[default]
+    High,
+}
+
+impl Quality {
+    pub const ALL: [Self; 3] = [Self::Low, Self::Medium, Self::High];
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Low => translate("low"),
+            Self::Medium => translate("medium"),
+            Self::High => translate("high"),
+        }
+    }
+}
+
+pub struct World {
+    ctx: Context,
+    assets: Rc<Assets>,
+    pub far_distance: f32,
+    pub near_distance: f32,
+    pub camera_near: f32,
+    pub road_ratio: f32,
+    pub player: Character,
+    pub tsunami_position: f32,
+    next_house: f32,
+    next_obstacle: f32,
+    skin: Rc<skin::Skin>,
+    houses: Vec<(Vec2<f32>, Rc<skin::Prop>)>,
+    obstacles: Vec<(Vec2<f32>, Rc<skin::Prop>)>,
+    characters: Vec<Character>,
+    game_speed: f32,
+    pub time: Option<f32>,
+    tsunami_animation: animation::Playback,
+    water: water::Water,
+    particles: particles::Particles,
+    lighting: lighting::Lighting,
+    post_processing: postprocess::PostProcessing,
+    music: Option<audio::Track>,
+    roar: Option<audio::Emitter>,
+    engine: Option<audio::Emitter>,
+    audio_stopped: bool,
+}
+
+impl World {
+    pub fn new(ctx: &Context) -> Self {
+        let settings = ctx.settings.borrow();
+        let assets = ctx.assets();
+        Self {
+            ctx: ctx.clone(),
+            skin: assets.skins.get(&settings.skin).clone(),
+            houses: Vec::new(),
+            obstacles: Vec::new(),
+            far_distance: 0.0,
+            near_distance: 10.0,
+            camera_near: 1.0,
+            road_ratio: 0.5,
+            player: Character::new(
+                assets.skins.get(&settings.skin).character.clone(),
+                vec2(0.0, 0.2),
+            ),
+            characters: Vec::new(),
+            tsunami_position: -500.0,
+            next_house: BEACH_START + 1.0,
+            next_obstacle: 10.0,
+            game_speed: 1.0,
+            time: None,
+            tsunami_animation: default(),
+            water: water::Water::new(&ctx.geng, &assets.water),
+            particles: default(),
+            lighting: lighting::Lighting::new(settings.time_of_day, settings.weather),
+            post_processing: postprocess::PostProcessing::new(&ctx.geng, &assets.postprocess),
+            music: None,
+            roar: None,
+            engine: None,
+            audio_stopped: false,
+            assets,
+        }
+    }
+    pub fn start(&mut self) {
+        if self.time.is_none() {
+            self.time = Some(0.0);
+        }
+    }
+    pub fn pause_audio(&mut self) {
+        let mut audio = self.ctx.audio.borrow_mut();
+        if let Some(track) = self.music {
+            audio.pause_music(track);
+        }
+        for emitter in self.roar.iter().chain(&self.engine) {
+            audio.pause_emitter(*emitter);
+        }
+    }
+    pub fn stop_audio(&mut self) {
+        self.pause_audio();
+        let mut audio = self.ctx.audio.borrow_mut();
+        for emitter in self.roar.take().into_iter().chain(self.engine.take()) {
+            audio.stop_emitter(emitter);
+        }
+        self.audio_stopped = true;
+    }
+    pub fn resume_audio(&mut self) {
+        let mut audio = self.ctx.audio.borrow_mut();
+        if let Some(track) = self.music {
+            audio.resume_music(track);
+        }
+        for emitter in self.roar.iter().chain(&self.engine) {
+            audio.resume_emitter(*emitter);
+        }
+    }
+    fn update_emitters(&mut self) {
+        let mut audio = self.ctx.audio.borrow_mut();
+        let listener = self.player.position;
+        if self.roar.is_none() {
+            self.roar = audio.start_emitter(&self.assets.sounds, "wave_roar");
+        }
+        if let Some(roar) = self.roar {
+            audio.move_emitter(roar, vec2(-listener.x, self.tsunami_position - listener.y));
+        }
+        if self.engine.is_none() {
+            self.engine = audio.start_emitter(&self.assets.sounds, "car_engine");
+        }
+        if let Some(engine) = self.engine {
+            let nearest = self
+                .obstacles
+                .iter()
+                .map(|&(position, _)| position - listener)
+                .min_by(|a, b| a.len().partial_cmp(&b.len()).unwrap());
+            match nearest {
+                Some(offset) => audio.move_emitter(engine, offset),
+                None => audio.mute_emitter(engine),
+            }
+        }
+    }
+    fn to_screen(&self, framebuffer: &ugli::Framebuffer, position: Vec3<f32>) -> (Vec2<f32>, f32) {
+        let framebuffer_size = framebuffer.size();
+        let scale = self.camera_near / (self.near_distance + self.camera_near - position.y);
+        let screen_position = vec2(
+            position.x * scale * self.road_ratio,
+            scale - position.z * scale,
+        );
+        (
+            vec2(
+                screen_position.x * framebuffer_size.y as f32 + framebuffer_size.x as f32 / 2.0,
+                framebuffer_size.y as f32 * 0.8 * (1.0 - screen_position.y),
+            ),
+            scale,
+        )
+    }
+    pub fn draw_texture(
+        &self,
+        framebuffer: &mut ugli::Framebuffer,
+        texture: &ugli::Texture,
+        position: Vec3<f32>,
+        origin: Vec2<f32>,
+        size: Size,
+        color: Color<f32>,
+    ) {
+        if position.y > self.near_distance + self.camera_near {
+            return;
+        }
+        let (screen_position, scale) = self.to_screen(framebuffer, position);
+        let size = match size {
+            Size::Fixed(width, height) => vec2(width, height),
+            _ => {
+                let height = match size {
+                    Size::FixedHeight(height) => framebuffer.size().y as f32 * 0.8 * height * scale,
+                    Size::FixedWidth(width) => {
+                        let height = width * texture.size().y as f32 / texture.size().x as f32;
+                        framebuffer.size().y as f32 * 0.8 * height * scale
+                    }
+                    _ => unreachable!(),
+                };
+                let size = texture.size().map(|x| x as f32);
+                vec2(height * size.x / size.y, height)
+            }
+        };
+        let aabb = AABB::pos_size(
+            screen_position - vec2(size.x * origin.x, size.y * origin.y),
+            size,
+        );
+        self.ctx
+            .geng
+            .draw_2d()
+            .textured_quad(framebuffer, aabb, texture, color);
+    }
+    fn draw_particle(
+        &self,
+        framebuffer: &mut ugli::Framebuffer,
+        position: Vec3<f32>,
+        size: f32,
+        color: Color<f32>,
+    ) {
+        if position.y > self.near_distance + self.camera_near {
+            return;
+        }
+        let (screen_position, scale) = self.to_screen(framebuffer, position);
+        let size = framebuffer.size().y as f32 * 0.8 * size * scale;
+        self.ctx.geng.draw_2d().quad(
+            framebuffer,
+            AABB::pos_size(screen_position - vec2(size, size) / 2.0, vec2(size, size)),
+            color,
+        );
+    }
+    fn look_at(&mut self, position: f32) {
+        self.near_distance = position + 2.0;
+        self.far_distance = position - 10.0;
+    }
+    fn random_house(&self) -> Rc<skin::Prop> {
+        if self.next_house > BEACH_END {
+            self.skin
+                .houses
+                .choose(&mut rand::thread_rng())
+                .unwrap()
+                .clone()
+        } else {
+            self.skin
+                .beach_houses
+                .choose(&mut rand::thread_rng())
+                .unwrap()
+                .clone()
+        }
+    }
+    pub fn game_finished(&self) -> bool {
+        self.tsunami_position > self.near_distance + self.camera_near
+    }
+    fn danger(&self) -> f32 {
+        if self.time.is_some() && !self.game_finished() {
+            clamp(
+                1.0 - (self.player.position.y - self.tsunami_position - 1.0) / 5.0,
+                0.0..=1.0,
+            )
+        } else {
+            0.0
+        }
+    }
+    fn draw_road(
+        &self,
+        framebuffer: &mut ugli::Framebuffer,
+        near_pos: f32,
+        far_pos: f32,
+        texture: &ugli::Texture,
+        color: Color<f32>,
+    ) {
+        let near_pos = near_pos.max(self.far_distance);
+        let far_pos = far_pos.min(self.near_distance);
+        if far_pos < near_pos {
+            return;
+        }
+        let mut road = Vec::new();
+        const N: usize = 1000; // DIRTY HACK KEK
+        for i in 0..N {
+            let near = near_pos + (far_pos - near_pos) * i as f32 / N as f32;
+            let far = near_pos + (far_pos - near_pos) * (i + 1) as f32 / N as f32;
+            road.push(geng::draw_2d::TexturedVertex {
+                a_pos: self
+                    .to_screen(framebuffer, vec3(-self.road_ratio, far, 0.0))
+                    .0,
+                a_color: color,
+                a_vt: vec2(0.0, far),
+            });
+            road.push(geng::draw_2d::TexturedVertex {
+                a_pos: self
+                    .to_screen(framebuffer, vec3(self.road_ratio, far, 0.0))
+                    .0,
+                a_color: color,
+                a_vt: vec2(1.0, far),
+            });
+            road.push(geng::draw_2d::TexturedVertex {
+                a_pos: self
+                    .to_screen(framebuffer, vec3(self.road_ratio, near, 0.0))
+                    .0,
+                a_color: color,
+                a_vt: vec2(1.0, near),
+            });
+            road.push(geng::draw_2d::TexturedVertex {
+                a_pos: self
+                    .to_screen(framebuffer, vec3(-self.road_ratio, far, 0.0))
+                    .0,
+                a_color: color,
+                a_vt: vec2(0.0, far),
+            });
+            road.push(geng::draw_2d::TexturedVertex {
+                a_pos: self
+                    .to_screen(framebuffer, vec3(self.road_ratio, near, 0.0))
+                    .0,
+                a_color: color,
+                a_vt: vec2(1.0, near),
+            });
+            road.push(geng::draw_2d::TexturedVertex {
+                a_pos: self
+                    .to_screen(framebuffer, vec3(-self.road_ratio, near, 0.0))
+                    .0,
+                a_color: color,
+                a_vt: vec2(0.0, near),
+            });
+        }
+        for v in &mut road {
+            v.a_vt.y *= 0.1;
+        }
+        self.ctx.geng.draw_2d().draw_textured(
+            framebuffer,
+            &road,
+            texture,
+            Color::WHITE,
+            ugli::DrawMode::Triangles,
+        );
+    }
+    fn post_effects(&self) -> postprocess::Effects {
+        let reduced_motion = self.ctx.settings.borrow().reduced_motion;
+        postprocess::Effects {
+            vignette: self.danger(),
+            motion_blur: if reduced_motion {
+                0.0
+            } else {
+                clamp((self.game_speed - 1.5) / 3.0, 0.0..=1.0)
+            },
+            underwater: if self.game_finished() && !reduced_motion {
+                1.0
+            } else {
+                0.0
+            },
+        }
+    }
+    fn draw_world(&self, framebuffer: &mut ugli::Framebuffer) {
+        let framebuffer_size = framebuffer.size();
+        let settings = self.ctx.settings.borrow();
+        let palette = self
+            .lighting
+            .palette(self.player.position.y)
+            .color_blind(settings.color_blind);
+        ugli::clear(framebuffer, Some(palette.sky), None);
+        self.assets.background.draw(
+            &self.ctx.geng,
+            framebuffer,
+            self.player.position,
+            palette.tint,
+        );
+        let beach_start = self
+            .to_screen(
+                framebuffer,
+                vec3(0.0, BEACH_START.min(self.near_distance), 0.0),
+            )
+            .0
+            .y;
+        let beach_end = self
+            .to_screen(
+                &framebuffer,
+                vec3(0.0, BEACH_END.min(self.near_distance), 0.0),
+            )
+            .0
+            .y;
+        self.ctx.geng.draw_2d().quad(
+            framebuffer,
+            AABB::pos_size(
+                vec2(0.0, beach_start),
+                vec2(
+                    framebuffer_size.x as f32,
+                    framebuffer_size.y as f32 * 0.8 - beach_start,
+                ),
+            ),
+            palette.sea,
+        );
+        self.ctx.geng.draw_2d().quad(
+            framebuffer,
+            AABB::pos_size(
+                vec2(0.0, beach_start),
+                vec2(framebuffer_size.x as f32, beach_end - beach_start),
+            ),
+            palette.sand,
+        );
+        self.ctx.geng.draw_2d().quad(
+            framebuffer,
+            AABB::pos_size(vec2(0.0, 0.0), vec2(framebuffer_size.x as f32, beach_end)),
+            palette.grass,
+        );
+        self.draw_road(
+            framebuffer,
+            BEACH_END,
+            self.near_distance,
+            &self.assets.road,
+            palette.tint,
+        );
+        self.draw_road(
+            framebuffer,
+            BEACH_START.min(self.near_distance),
+            BEACH_END.min(self.near_distance),
+            &self.assets.sand_road,
+            palette.tint,
+        );
+        self.draw_road(
+            framebuffer,
+            0.0,
+            BEACH_START.min(self.near_distance),
+            &self.assets.pierce,
+            palette.tint,
+        );
+        let mut sprites: Vec<Sprite> = Vec::new();
+        for (position, prop) in &self.houses {
+            sprites.push(Sprite::Texture(
+                &prop.texture,
+                position.extend(0.0),
+                prop.origin,
+                Size::FixedWidth(prop.width),
+            ));
+        }
+        if !self.game_finished() {
+            for (position, prop) in &self.obstacles {
+                sprites.push(Sprite::Texture(
+                    &prop.texture,
+                    position.extend(0.0),
+                    prop.origin,
+                    Size::FixedWidth(prop.width),
+                ));
+            }
+            if self.tsunami_position < -4.0 {
+                let mut result = self.player.draw();
+                result.0 = &self.assets.sit;
+                result.2.y = 0.3;
+                result.3 = Size::FixedWidth(PLAYER_SIZE * 4.0);
+                sprites.push(result.into());
+            } else {
+                sprites.push(self.player.draw().into());
+            }
+            for character in &self.characters {
+                sprites.push(character.draw().into());
+            }
+            if settings.quality != Quality::Low {
+                for particle in self.particles.iter() {
+                    sprites.push(Sprite::Particle(
+                        particle.position,
+                        particle.size,
+                        particle.color(),
+                    ));
+                }
+            }
+            if self.water.enabled() && settings.quality != Quality::Low {
+                sprites.push(Sprite::Water(
+                    vec3(0.0, self.tsunami_position, 0.0),
+                    vec2(0.5, 0.2),
+                    2.0,
+                ));
+            } else {
+                sprites.push(Sprite::Texture(
+                    self.tsunami_animation.frame(&self.assets.tsunami),
+                    vec3(0.0, self.tsunami_position, 0.0),
+                    vec2(0.5, 0.2),
+                    Size::Fixed(1000.0, 2.0),
+                ));
+            }
+        }
+        sprites.sort_by_key(|sprite| r32(sprite.position().y));
+        for sprite in sprites {
+            match sprite {
+                Sprite::Texture(texture, position, origin, Size::Fixed(_, height)) => {
+                    let (pos, scale) = self.to_screen(framebuffer, position);
+                    let size = height * scale * framebuffer_size.y as f32 * 0.8;
+                    let y = pos.y - size * origin.y;
+                    let texture_width = framebuffer_size.x as f32
+                        / (size * texture.size().x as f32 / texture.size().y as f32);
+                    let vt1 = -texture_width / 2.0 + 0.5;
+                    let vt2 = texture_width / 2.0 + 0.5;
+                    let y1 = y;
+                    let y2 = y1 + size;
+                    self.ctx.geng.draw_2d().draw_textured(
+                        framebuffer,
+                        &[
+                            geng::draw_2d::TexturedVertex {
+                                a_color: palette.tint,
+                                a_pos: vec2(0.0, y1),
+                                a_vt: vec2(vt1, 0.0),
+                            },
+                            geng::draw_2d::TexturedVertex {
+                                a_color: palette.tint,
+                                a_pos: vec2(framebuffer_size.x as f32, y1),
+                                a_vt: vec2(vt2, 0.0),
+                            },
+                            geng::draw_2d::TexturedVertex {
+                                a_color: palette.tint,
+                                a_pos: vec2(framebuffer_size.x as f32, y2),
+                                a_vt: vec2(vt2, 1.0),
+                            },
+                            geng::draw_2d::TexturedVertex {
+                                a_color: palette.tint,
+                                a_pos: vec2(0.0, y2),
+                                a_vt: vec2(vt1, 1.0),
+                            },
+                        ],
+                        texture,
+                        Color::WHITE,
+                        ugli::DrawMode::TriangleFan,
+                    );
+                }
+                Sprite::Texture(texture, position, origin, size) => {
+                    self.draw_texture(framebuffer, texture, position, origin, size, palette.tint);
+                }
+                Sprite::Water(position, origin, height) => {
+                    let (pos, scale) = self.to_screen(framebuffer, position);
+                    let size = height * scale * framebuffer_size.y as f32 * 0.8;
+                    self.water.draw(
+                        framebuffer,
+                        pos.x,
+                        pos.y - size * origin.y,
+                        size,
+                        palette.tint,
+                    );
+                }
+                Sprite::Particle(position, size, color) => {
+                    self.draw_particle(framebuffer, position, size, palette.tinted(color));
+                }
+            }
+        }
+        if settings.quality != Quality::Low {
+            self.lighting
+                .draw_rain(&self.ctx.geng, framebuffer, &palette);
+        }
+        if self.game_finished() {
+            self.ctx.geng.draw_2d().quad(
+                framebuffer,
+                AABB::pos_size(
+                    vec2(0.0, 0.0),
+                    vec2(framebuffer_size.x as f32, framebuffer_size.y as f32 * 0.8),
+                ),
+                Color::rgba(0.0, 0.5, 1.0, 0.5),
+            );
+        }
+    }
+    pub fn draw(&mut self, framebuffer: &mut ugli::Framebuffer) {
+        let settings = self.ctx.settings.borrow();
+        if settings.post_processing.enabled && settings.quality == Quality::High {
+            let mut texture = self.post_processing.take_texture(framebuffer.size());
+            self.draw_world(&mut ugli::Framebuffer::new_color(
+                self.ctx.geng.ugli(),
+                ugli::ColorAttachment::Texture(&mut texture),
+            ));
+            let effects = self.post_effects();
+            self.post_processing
+                .draw(framebuffer, texture, &settings.post_processing, effects);
+        } else {
+            self.draw_world(framebuffer);
+        }
+    }
+    fn reload_assets(&mut self) {
+        let assets = self.ctx.assets();
+        if Rc::ptr_eq(&assets, &self.assets) {
+            return;
+        }
+        let settings = self.ctx.settings.borrow();
+        self.skin = assets.skins.get(&settings.skin).clone();
+        self.player.set_assets(self.skin.character.clone());
+        for character in &mut self.characters {
+            character.set_assets(self.skin.character.clone());
+        }
+        self.water = water::Water::new(&self.ctx.geng, &assets.water);
+        self.post_processing =
+            postprocess::PostProcessing::new(&self.ctx.geng, &assets.postprocess);
+        self.assets = assets;
+    }
+    pub fn update(&mut self, delta_time: f32, steering: f32) {
+        self.reload_assets();
+        {
+            let mut audio = self.ctx.audio.borrow_mut();
+            if self.music.is_none() {
+                self.music = Some(audio.play_music(&self.assets.music));
+            }
+            audio.set_intensity(self.danger() as f64);
+            audio.update(delta_time as f64);
+        }
+        let mut delta_time = delta_time;
+        self.lighting.update(delta_time);
+        self.post_processing.update(delta_time);
+        if self.time.is_none() {
+            delta_time = 0.0;
+        } else if !self.game_finished() {
+            self.time = Some(self.time.unwrap() + delta_time);
+        }
+        self.tsunami_animation
+            .update(&self.assets.tsunami, delta_time);
+        self.water.update(delta_time);
+        if self.tsunami_position < -4.0 {
+            delta_time *= -self.tsunami_position;
+        } else if self.player.state == character::State::Run {
+            self.game_speed += self.ctx.settings.borrow().difficulty.speed_growth() * delta_time;
+        } else {
+            self.game_speed = 2.0;
+        }
+        let delta_time = delta_time * self.game_speed;
+        if self.player.state == character::State::Run && self.tsunami_position > -4.0 {
+            if self.player.velocity.y == 0.0 {
+                self.ctx
+                    .audio
+                    .borrow_mut()
+                    .trigger(&self.assets.sounds, "player_cry");
+            }
+            self.player.velocity.x = steering;
+            self.player.velocity.y += clamp_abs(1.0 - self.player.velocity.y, delta_time * 5.0);
+        }
+        let previous_y = self.player.position.y;
+        for event in self.player.update(delta_time) {
+            self.ctx
+                .audio
+                .borrow_mut()
+                .trigger(&self.assets.sounds, event);
+        }
+        self.player.position.x = clamp(
+            self.player.position.x,
+            -self.road_ratio + PLAYER_SIZE..=self.road_ratio - PLAYER_SIZE,
+        );
+        let was_ok = self.player.state == character::State::Run;
+        let running: Vec<bool> = self
+            .characters
+            .iter()
+            .map(|character| character.state == character::State::Run)
+            .collect();
+        let listener = self.player.position;
+        for &(position, _) in &self.obstacles {
+            for character in self
+                .characters
+                .iter_mut()
+                .chain(std::iter::once(&mut self.player))
+            {
+                if character.check_hit(position, OBSTACLE_SIZE) && character.fall_side() {
+                    self.ctx.audio.borrow_mut().trigger_at(
+                        &self.assets.sounds,
+                        "collision",
+                        position - listener,
+                    );
+                    self.particles.sparks((character.position + position) / 2.0);
+                    self.particles.dust(character.position);
+                }
+            }
+        }
+        for character in &mut self.characters {
+            if character.position.y < self.tsunami_position + 1.0 {
+                let fell = if rand::thread_rng().gen_bool(0.5) {
+                    character.fall()
+                } else {
+                    character.fall_side()
+                };
+                if fell {
+                    self.particles.dust(character.position);
+                }
+            }
+            if self.player.check_hit(character.position, PLAYER_SIZE) {
+                if self.player.fall() {
+                    self.particles.dust(self.player.position);
+                }
+                if character.fall_side() {
+                    self.particles.dust(character.position);
+                }
+            }
+        }
+        for (character, was_running) in self.characters.iter().zip(running) {
+            if was_running && character.state != character::State::Run {
+                self.ctx.audio.borrow_mut().trigger_at(
+                    &self.assets.sounds,
+                    "npc_fall",
+                    character.position - self.player.position,
+                );
+            }
+        }
+        if self.player.state != character::State::Run && was_ok {
+            self.ctx
+                .audio
+                .borrow_mut()
+                .trigger(&self.assets.sounds, "player_hit");
+        } else if self.player.state == character::State::Run {
+            for &(position, _) in &self.obstacles {
+                if previous_y + HONK_DISTANCE < position.y
+                    && position.y <= self.player.position.y + HONK_DISTANCE
+                    && rand::thread_rng().gen_bool(HONK_CHANCE)
+                {
+                    self.ctx.audio.borrow_mut().trigger_at(
+                        &self.assets.sounds,
+                        "car_honk",
+                        position - self.player.position,
+                    );
+                }
+                if previous_y < position.y
+                    && position.y <= self.player.position.y
+                    && (position.x - self.player.position.x).abs()
+                        < (OBSTACLE_SIZE + PLAYER_SIZE) * NEAR_MISS_RATIO
+                {
+                    self.ctx
+                        .audio
+                        .borrow_mut()
+                        .trigger(&self.assets.sounds, "near_miss");
+                }
+            }
+        }
+        self.tsunami_position += delta_time;
+        self.particles.spray(self.tsunami_position, delta_time);
+        self.particles.update(delta_time);
+        self.look_at(self.player.position.y);
+        while self.near_distance + self.camera_near > self.next_house {
+            if self.next_house > BEACH_END {
+                self.houses
+                    .push((vec2(1.3, self.next_house), self.random_house()));
+                self.houses
+                    .push((vec2(-1.3, self.next_house), self.random_house()));
+            } else {
+                if rand::thread_rng().gen_bool(0.5) {
+                    self.houses
+                        .push((vec2(1.3, self.next_house), self.random_house()));
+                } else {
+                    self.houses
+                        .push((vec2(-1.3, self.next_house), self.random_house()));
+                }
+            }
+            self.next_house += 1.0;
+        }
+        while self.near_distance + self.camera_near > self.next_obstacle {
+            if rand::thread_rng().gen_bool(0.7) {
+                let mut character = Character::new(
+                    self.skin.character.clone(),
+                    vec2(
+                        rand::thread_rng().gen_range(
+                            -self.road_ratio + PLAYER_SIZE..=self.road_ratio - PLAYER_SIZE,
+                        ),
+                        self.next_obstacle,
+                    ),
+                );
+                character.velocity = vec2(0.0, rand::thread_rng().gen_range(0.3..0.7));
+                self.characters.push(character);
+            } else {
+                self.obstacles.push((
+                    vec2(
+                        if rand::thread_rng().gen_bool(0.5) {
+                            1.0
+                        } else {
+                            -1.0
+                        } * 0.25,
+                        self.next_obstacle,
+                    ),
+                    self.skin
+                        .cars
+                        .choose(&mut rand::thread_rng())
+                        .unwrap()
+                        .clone(),
+                ));
+            }
+            self.next_obstacle += self.ctx.settings.borrow().difficulty.obstacle_spacing();
+        }
+        let near_distance = self.near_distance;
+        let far_distance = self.far_distance;
+        let camera_near = self.camera_near;
+        self.houses.retain(|&(position, _)| {
+            far_distance <= position.y && position.y <= near_distance + camera_near
+        });
+        self.obstacles.retain(|&(position, _)| {
+            far_distance <= position.y && position.y <= near_distance + camera_near
+        });
+        self.characters.retain(|&Character { position, .. }| {
+            far_distance <= position.y && position.y <= near_distance + camera_near
+        });
+        let listener = self.player.position;
+        for character in &mut self.characters {
+            let position = character.position;
+            for event in character.update(delta_time) {
+                self.ctx.audio.borrow_mut().trigger_at(
+                    &self.assets.sounds,
+                    event,
+                    position - listener,
+                );
+            }
+        }
+        if !self.audio_stopped {
+            self.update_emitters();
+        }
+    }
+}
+
+impl Drop for World {
+    fn drop(&mut self) {
+        let mut audio = self.ctx.audio.borrow_mut();
+        if let Some(track) = self.music {
+            audio.stop_music(track);
+        }
+        for emitter in self.roar.iter().chain(&self.engine) {
+            audio.stop_emitter(*emitter);
+        }
+    }
+}
@@ -0,0 +1,56 @@
+varying vec2 v_vt;
+
+#ifdef VERTEX_SHADER
+attribute vec2 a_pos;
+void main() {
+    v_vt = (a_pos + 1.0) / 2.0;
+    gl_Position = vec4(a_pos, 0.0, 1.0);
+}
+#endif
+
+#ifdef FRAGMENT_SHADER
+uniform sampler2D u_texture;
+uniform ivec2 u_texture_size;
+uniform float u_time;
+uniform float u_vignette;
+uniform float u_motion_blur;
+uniform float u_underwater;
+uniform float u_crt;
+
+const vec2 HORIZON = vec2(0.5, 0.8);
+const int BLUR_SAMPLES = 8;
+
+vec2 crt_warp(vec2 uv) {
+    vec2 d = uv - 0.5;
+    return uv + d * dot(d, d) * 0.15 * u_crt;
+}
+
+vec4 sample_blurred(vec2 uv) {
+    vec4 color = vec4(0.0);
+    vec2 step = (HORIZON - uv) * 0.02 * u_motion_blur;
+    for (int i = 0; i < BLUR_SAMPLES; i++) {
+        color += texture2D(u_texture, uv + step * float(i));
+    }
+    return color / float(BLUR_SAMPLES);
+}
+
+void main() {
+    vec2 uv = crt_warp(v_vt);
+    uv.x += sin(uv.y * 30.0 + u_time * 3.0) * 0.005 * u_underwater;
+    uv.y += cos(uv.x * 25.0 + u_time * 2.0) * 0.004 * u_underwater;
+    vec4 color = sample_blurred(uv);
+    if (u_crt > 0.0) {
+        float offset = 1.5 / float(u_texture_size.x);
+        color.r = sample_blurred(uv + vec2(offset, 0.0)).r;
+        color.b = sample_blurred(uv - vec2(offset, 0.0)).b;
+        color.rgb *= 1.0 - u_crt * 0.25 * (0.5 + 0.5 * sin(uv.y * float(u_texture_size.y) * 3.14159));
+        if (uv.x < 0.0 || uv.x > 1.0 || uv.y < 0.0 || uv.y > 1.0) {
+            color = vec4(0.0, 0.0, 0.0, 1.0);
+        }
+    }
+    color.rgb = mix(color.rgb, color.rgb * vec3(0.4, 0.7, 1.0), u_underwater * 0.5);
+    float d = length(v_vt - 0.5) * 1.4;
+    color.rgb *= 1.0 - u_vignette * smoothstep(0.4, 1.0, d);
+    gl_FragColor = vec4(color.rgb, 1.0);
+}
+#endif
@@ -0,0 +1,45 @@
+varying vec2 v_vt;
+
+#ifdef VERTEX_SHADER
+attribute vec2 a_pos;
+attribute vec2 a_vt;
+uniform ivec2 u_framebuffer_size;
+void main() {
+    v_vt = a_vt;
+    gl_Position = vec4(a_pos / vec2(u_framebuffer_size) * 2.0 - 1.0, 0.0, 1.0);
+}
+#endif
+
+#ifdef FRAGMENT_SHADER
+uniform float u_time;
+uniform vec4 u_color;
+uniform vec4 u_deep_color;
+uniform vec4 u_shallow_color;
+uniform vec4 u_foam_color;
+uniform float u_crest_height;
+uniform float u_amplitude;
+uniform float u_frequency;
+uniform float u_speed;
+uniform float u_foam_width;
+
+float wave_height(float x) {
+    float t = u_time * u_speed;
+    float h = u_crest_height;
+    h += u_amplitude * sin(x * u_frequency + t);
+    h += u_amplitude * 0.5 * sin(x * u_frequency * 2.3 - t * 1.7);
+    h += u_amplitude * 0.25 * sin(x * u_frequency * 5.1 + t * 2.9);
+    return h;
+}
+
+void main() {
+    float h = wave_height(v_vt.x);
+    if (v_vt.y > h) {
+        discard;
+    }
+    vec4 color = mix(u_deep_color, u_shallow_color, v_vt.y / h);
+    float ripple = 0.5 + 0.5 * sin(v_vt.x * u_frequency * 13.0 + v_vt.y * 40.0 - u_time * u_speed * 4.0);
+    float foam = smoothstep(h - u_foam_width * (0.5 + ripple), h, v_vt.y);
+    color = mix(color, u_foam_color, foam);
+    gl_FragColor = color * u_color;
+}
+#endif